@@ -0,0 +1,245 @@
+//! Async driver built on `embedded-hal-async`
+//!
+//! Offers the conversion related functionality of the blocking driver. While a conversion is
+//! running the executor is free to run other tasks, the end of the conversion is awaited on the
+//! INTERRUPT pin or by polling the status register with an async delay in between.
+
+use core::{future::{poll_fn, Future}, pin::pin, task::Poll};
+
+use embedded_hal_async::{delay::DelayNs, digital::Wait, spi::{Operation, SpiDevice}};
+
+use crate::{
+    channel_word, check_channel, check_probe, frame_header, Average, DeviceVariant, GlobalConfig, LTC2983Channel, LTC2983Error,
+    LTC2983Result, LTC2983Status, LTC2983Variant, NoInterruptPin, TemperatureReading, TemperatureUnit, ThermalProbeType,
+    DEFAULT_CONVERSION_TIMEOUT_US, DEFAULT_MAX_RETRIES, GLOBAL_CONFIG_REGISTER, LTC2983_READ, LTC2983_WRITE,
+    MULTI_CHANNEL_MASK_REGISTER, START_CONVERSION_COMMAND, STATUS_REGISTER,
+};
+
+// a conversion takes at least 82ms, polling every 10ms keeps the bus mostly idle
+const STATUS_POLL_INTERVAL_MS: u32 = 10;
+
+pub struct LTC2983<SPI, D, V = LTC2983Variant, INT = NoInterruptPin> {
+    spi_device: SPI,
+    delay: D,
+    temperature_unit: TemperatureUnit,
+    _variant: V,
+    interrupt: Option<INT>,
+    conversion_timeout_us: u32,
+    max_retries: u32
+}
+
+impl<SPI, D> LTC2983<SPI, D> {
+    pub fn new(spi_device: SPI, delay: D) -> Self {
+        Self::with_variant(spi_device, delay, LTC2983Variant)
+    }
+}
+
+impl<SPI, D, V> LTC2983<SPI, D, V> where V: DeviceVariant {
+    /// create a driver for a specific member of the device family
+    pub fn with_variant(spi_device: SPI, delay: D, variant: V) -> Self {
+        LTC2983 {
+            spi_device,
+            delay,
+            temperature_unit: Default::default(),
+            _variant: variant,
+            interrupt: None,
+            conversion_timeout_us: DEFAULT_CONVERSION_TIMEOUT_US,
+            max_retries: DEFAULT_MAX_RETRIES
+        }
+    }
+
+    /// await the INTERRUPT output of the device instead of polling the status register
+    pub fn with_interrupt_pin<INT: Wait>(self, interrupt: INT) -> LTC2983<SPI, D, V, INT> {
+        LTC2983 {
+            spi_device: self.spi_device,
+            delay: self.delay,
+            temperature_unit: self.temperature_unit,
+            _variant: self._variant,
+            interrupt: Some(interrupt),
+            conversion_timeout_us: self.conversion_timeout_us,
+            max_retries: self.max_retries
+        }
+    }
+
+    /// time the averaging helpers wait for a single channel conversion before giving up (default 500ms),
+    /// conversions of multiple channels may take this long per channel
+    pub fn set_conversion_timeout(&mut self, timeout_us: u32) {
+        self.conversion_timeout_us = timeout_us;
+    }
+
+    /// number of rounds with invalid results or failed readouts the averaging helpers repeat before giving up (default 3)
+    pub fn set_max_retries(&mut self, max_retries: u32) {
+        self.max_retries = max_retries;
+    }
+}
+
+impl<SPI, D, V, INT> LTC2983<SPI, D, V, INT> where SPI: SpiDevice, D: DelayNs, V: DeviceVariant, INT: Wait {
+    //write `data` to the memory of the device starting at `address`
+    async fn write_memory(&mut self, address: u16, data: &[u8]) -> Result<(), LTC2983Error<SPI::Error>> {
+        let header = frame_header(LTC2983_WRITE, address);
+        self.spi_device.transaction(&mut [Operation::Write(&header), Operation::Write(data)]).await?;
+        Ok(())
+    }
+
+    //fill `data` from the memory of the device starting at `address`
+    async fn read_memory(&mut self, address: u16, data: &mut [u8]) -> Result<(), LTC2983Error<SPI::Error>> {
+        let header = frame_header(LTC2983_READ, address);
+        self.spi_device.transaction(&mut [Operation::Write(&header), Operation::Read(data)]).await?;
+        Ok(())
+    }
+
+    /// unit of the temperatures returned by the device, as last written to or read from the global configuration
+    pub fn temperature_unit(&self) -> TemperatureUnit {
+        self.temperature_unit
+    }
+
+    pub async fn write_global_config(&mut self, config: &GlobalConfig) -> Result<(), LTC2983Error<SPI::Error>> {
+        self.write_memory(GLOBAL_CONFIG_REGISTER, &[config.to_bits()]).await?;
+        self.temperature_unit = config.unit();
+        Ok(())
+    }
+
+    pub async fn read_global_config(&mut self) -> Result<GlobalConfig, LTC2983Error<SPI::Error>> {
+        let mut config = [0];
+        self.read_memory(GLOBAL_CONFIG_REGISTER, &mut config).await?;
+        let config = GlobalConfig::from(config[0]);
+        self.temperature_unit = config.unit();
+        Ok(config)
+    }
+
+    //read device status
+    pub async fn status(&mut self) -> Result<LTC2983Status, LTC2983Error<SPI::Error>> {
+        let mut status = [0];
+        self.read_memory(STATUS_REGISTER, &mut status).await?;
+        Ok(LTC2983Status::from(status[0]))
+    }
+
+    //write channel configuration
+    pub async fn setup_channel(&mut self,
+                               probe: ThermalProbeType,
+                               channel: &LTC2983Channel) -> Result<(), LTC2983Error<SPI::Error>>
+    {
+        check_probe::<V, _>(&probe, channel)?;
+        let word = channel_word(&probe, channel)?;
+        // custom sensors need their data to be uploaded before the channel can reference it
+        if let Some((Some(address), data)) = probe.custom_data() {
+            self.write_memory(address, &data).await?;
+        }
+        self.write_memory(channel.start_address(), &word.to_be_bytes()).await
+    }
+
+    pub async fn start_conversion(&mut self, channel: &LTC2983Channel) -> Result<(), LTC2983Error<SPI::Error>> {
+        check_channel::<V, _>(channel)?;
+        self.write_memory(STATUS_REGISTER, &[START_CONVERSION_COMMAND | channel.identifier() as u8]).await
+    }
+
+    pub async fn start_multi_conversion(&mut self, channels: &[LTC2983Channel]) -> Result<(), LTC2983Error<SPI::Error>> {
+        let mut mask: u32 = 0x0;
+        for chan in channels {
+            check_channel::<V, _>(chan)?;
+            mask |= chan.mask();
+        }
+        self.write_memory(MULTI_CHANNEL_MASK_REGISTER, &mask.to_be_bytes()).await?;
+        // a conversion of channel 0 converts all channels of the multi channel mask
+        self.write_memory(STATUS_REGISTER, &[START_CONVERSION_COMMAND]).await
+    }
+
+    /// wait until the running conversion is done
+    ///
+    /// the INTERRUPT pin is awaited if one was provided, otherwise the status register is polled
+    pub async fn wait_for_completion(&mut self) -> Result<(), LTC2983Error<SPI::Error>> {
+        match &mut self.interrupt {
+            // the INTERRUPT output is low while the device is busy
+            Some(interrupt) => interrupt.wait_for_high().await.map_err(|_| LTC2983Error::InterruptPinError)?,
+            None => {
+                while !self.status().await?.done {
+                    self.delay.delay_ms(STATUS_POLL_INTERVAL_MS).await;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// wait until the running conversion is done or `timeout_us` has passed
+    ///
+    /// the INTERRUPT pin is awaited if one was provided, otherwise the status register is polled
+    pub async fn wait_done(&mut self, timeout_us: u32) -> Result<(), LTC2983Error<SPI::Error>> {
+        match &mut self.interrupt {
+            Some(interrupt) => {
+                // race the INTERRUPT pin against the timeout
+                let mut done = pin!(interrupt.wait_for_high());
+                let mut timeout = pin!(self.delay.delay_us(timeout_us));
+                poll_fn(|cx| match done.as_mut().poll(cx) {
+                    Poll::Ready(res) => Poll::Ready(res.map_err(|_| LTC2983Error::InterruptPinError)),
+                    Poll::Pending => timeout.as_mut().poll(cx).map(|_| Err(LTC2983Error::ConversionTimeout))
+                }).await
+            }
+            None => {
+                let mut waited_us = 0;
+                while !self.status().await?.done {
+                    if waited_us >= timeout_us {
+                        return Err(LTC2983Error::ConversionTimeout);
+                    }
+                    self.delay.delay_ms(STATUS_POLL_INTERVAL_MS).await;
+                    waited_us += STATUS_POLL_INTERVAL_MS * 1000;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// read the result of a temperature channel labelled with the unit of the global configuration
+    pub async fn read_temperature(&mut self, channel: &LTC2983Channel) -> Result<TemperatureReading, LTC2983Error<SPI::Error>> {
+        check_channel::<V, _>(channel)?;
+        let mut result = [0; 4];
+        self.read_memory(channel.result_address(), &mut result).await?;
+        Ok(TemperatureReading::new(LTC2983Result::from(result), self.temperature_unit))
+    }
+
+    /// read the result of a channel configured as direct adc, the result holds the voltage in V
+    pub async fn read_voltage(&mut self, channel: &LTC2983Channel) -> Result<LTC2983Result, LTC2983Error<SPI::Error>> {
+        check_channel::<V, _>(channel)?;
+        let mut result = [0; 4];
+        self.read_memory(channel.result_address(), &mut result).await?;
+        Ok(LTC2983Result::from_voltage_bytes(result))
+    }
+
+    pub async fn read_multi_temperature<const N: usize>(&mut self, channels: &[LTC2983Channel; N]) -> [Result<TemperatureReading, LTC2983Error<SPI::Error>>; N] {
+        let mut results = [const { None }; N];
+        for (result, chan) in results.iter_mut().zip(channels) {
+            *result = Some(self.read_temperature(chan).await);
+        }
+        results.map(|result| result.expect("every channel is read"))
+    }
+
+    ///do multiple rounds of conversion for a channel then calculate the average of the temperatures read out
+    ///
+    /// the average is in the unit returned by [`LTC2983::temperature_unit`]
+    ///
+    /// rounds with invalid results or failed readouts are repeated up to the configured number of retries
+    pub async fn get_temperature_avg(&mut self, channel: &LTC2983Channel, rounds: usize) -> Result<f32, LTC2983Error<SPI::Error>> {
+        let mut average = Average::new(rounds, self.max_retries)?;
+        while !average.complete() {
+            self.start_conversion(channel).await?;
+            self.wait_done(self.conversion_timeout_us).await?;
+            average.add([self.read_temperature(channel).await])?;
+        }
+        Ok(average.result()[0])
+    }
+
+    ///do multiple rounds of conversion for multiple channels then calculate the average of the temperatures read out
+    ///
+    /// the averages are in the unit returned by [`LTC2983::temperature_unit`]
+    ///
+    /// rounds with invalid results or failed readouts are repeated up to the configured number of retries
+    pub async fn get_multi_temperature_avg<const N: usize>(&mut self, channels: &[LTC2983Channel; N], rounds: usize) -> Result<[f32; N], LTC2983Error<SPI::Error>> {
+        let mut average = Average::new(rounds, self.max_retries)?;
+        let timeout_us = self.conversion_timeout_us.saturating_mul(N as u32);
+        while !average.complete() {
+            self.start_multi_conversion(channels).await?;
+            self.wait_done(timeout_us).await?;
+            average.add(self.read_multi_temperature(channels).await)?;
+        }
+        Ok(average.result())
+    }
+}
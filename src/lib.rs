@@ -6,61 +6,87 @@
 //!
 //! Contributions welcome 💪
 //!
+//! The driver is `no_std` and does not allocate. The default `std` feature only adds the
+//! implementations of `std::error::Error` for the error types and the
+//! `sim` module, a simulated device to run the drivers against without hardware.
+//!
 //! - [x] Theromcouple J,K,E,N,R,S,T,B
-//! - [ ] Custom Thermocouple
+//! - [x] Custom Thermocouple
 //! - [x] RTD
-//! - [ ] Thermistor
+//! - [x] Thermistor
 //! - [x] Sense Resistor
 //! - [x] Diode
-//! - [ ] Direct ADC
+//! - [x] Direct ADC
 //!
 //!# Example
-//!``` rust
+//!``` rust,ignore
+//!    // with the INTERRUPT pin connected use `LTC2983::new(device).with_interrupt_pin(pin)` to avoid polling the status
 //!    let mut ltc = LTC2983::new(device);
 //!
-//!    let _ = ltc.setup_channel(ltc2983::ThermalProbeType::Diode(ltc2983::DiodeParameters::default().ideality_factor(1.).excitation_current(ltc2983::DiodeExcitationCurrent::I20uA).num_reading(ltc2983::DiodeReadingCount::READ3)), ltc2983::LTC2983Channel::CH2);
-//!    let _ = ltc.setup_channel(ltc2983::ThermalProbeType::Thermocouple_T(ThermocoupleParameters::default().cold_junction(ltc2983::LTC2983Channel::CH2)), ltc2983::LTC2983Channel::CH1);
+//!    ltc.setup_channel(ltc2983::ThermalProbeType::Diode(ltc2983::DiodeParameters::default().ideality_factor(1.).excitation_current(ltc2983::DiodeExcitationCurrent::I20uA).num_reading(ltc2983::DiodeReadingCount::READ3)), &ltc2983::LTC2983Channel::CH2)?;
+//!    ltc.setup_channel(ltc2983::ThermalProbeType::Thermocouple_T(ThermocoupleParameters::default().cold_junction(ltc2983::LTC2983Channel::CH2)), &ltc2983::LTC2983Channel::CH1)?;
 //!
 //!    loop {
-//!        let _ = ltc.start_conversion(ltc2983::LTC2983Channel::CH1);
-//!        let mut status = ltc.status().unwrap();
-//!        while !status.done() {
-//!            status = ltc.status().unwrap();
-//!        }
-//!        let result = ltc.read_temperature(ltc2983::LTC2983Channel::CH1);
-//!        println!("{result:#?}");
+//!        ltc.start_conversion(&ltc2983::LTC2983Channel::CH1)?;
+//!        // `delay` is any `embedded_hal::delay::DelayNs`, give up if the conversion does not finish within 500ms
+//!        ltc.wait_done(&mut delay, 500_000)?;
+//!        let reading = ltc.read_temperature(&ltc2983::LTC2983Channel::CH1)?;
+//!        println!("{:?} in {:?}", reading.result(), reading.unit());
 //!        sleep(Duration::new(1, 0));
 //!    }
 //!
 //!```
 
-use std::{convert::TryInto};
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use bytebuffer::ByteBuffer;
-use embedded_hal::spi::{SpiDevice, SpiBus};
-use fixed::{FixedU32, types::extra::{U10, U20}, FixedI32};
+use bitflags::bitflags;
+use embedded_hal::{spi::{SpiDevice, Operation}, digital::{self, InputPin}, delay::DelayNs};
+use fixed::{FixedU32, types::extra::{LeEqU32, U6, U10, U20, U21}, FixedI32};
 use serde::{Serialize, Deserialize};
 use thiserror::Error;
 
+pub mod asynch;
+#[cfg(feature = "std")]
+pub mod sim;
+
 const LTC2983_WRITE: u8 = 0x2;
 const LTC2983_READ: u8 = 0x3;
 
 const STATUS_REGISTER: u16 = 0x000;
-//const GLOBAL_CONFIG_REGISTER: u16 = 0x0F0;
+const START_CONVERSION_COMMAND: u8 = 0x80;
+const SLEEP_COMMAND: u8 = 0x97;
+const GLOBAL_CONFIG_REGISTER: u16 = 0x0F0;
 const MULTI_CHANNEL_MASK_REGISTER: u16 = 0x0F4;
-
-#[derive(Debug)]
+const MUX_CONFIG_DELAY_REGISTER: u16 = 0x0FF;
+// the mux configuration delay is set in steps of 100µs
+const MUX_CONFIG_DELAY_STEP_US: u32 = 100;
+const MUX_CONFIG_DELAY_MAX_US: u32 = 255 * MUX_CONFIG_DELAY_STEP_US;
+const CUSTOM_DATA_START_ADDRESS: u16 = 0x250;
+const CUSTOM_DATA_END_ADDRESS: u16 = 0x3CF;
+// custom data pointers address the custom data ram in blocks of 6 bytes (the size of one table entry)
+const CUSTOM_DATA_BLOCK_SIZE: u16 = 6;
+const CUSTOM_DATA_SIZE: usize = (CUSTOM_DATA_END_ADDRESS - CUSTOM_DATA_START_ADDRESS + 1) as usize;
+// the eeprom of the LTC2984 is unlocked by writing this key before every eeprom command
+const EEPROM_KEY_REGISTER: u16 = 0x0B0;
+const EEPROM_KEY: u32 = 0xA53C0F5A;
+const EEPROM_STATUS_REGISTER: u16 = 0x0D0;
+const EEPROM_WRITE_COMMAND: u8 = 0x95;
+const EEPROM_READ_COMMAND: u8 = 0x96;
+// the slowest single conversions take about 250ms, leave room for the mux configuration delay
+const DEFAULT_CONVERSION_TIMEOUT_US: u32 = 500_000;
+const DEFAULT_MAX_RETRIES: u32 = 3;
+// time between two checks for the end of a conversion while waiting with a timeout
+const DONE_POLL_INTERVAL_US: u32 = 1_000;
+// the device initializes within 200ms after waking up, the eeprom commands finish in a similar time
+const READY_TIMEOUT_US: u32 = 500_000;
+
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub enum SensorConfiguration {
+    #[default]
     SingleEnded,
     Differential
 }
 
-impl Default for SensorConfiguration {
-    fn default() -> Self {
-        Self::SingleEnded
-    }
-}
-
 impl SensorConfiguration {
     pub fn identifier(&self) -> u64 {
         match self {
@@ -68,25 +94,27 @@ impl SensorConfiguration {
             SensorConfiguration::Differential => 0,
         }
     }
+
+    pub fn from_identifier(identifier: u64) -> Option<Self> {
+        match identifier {
+            1 => Some(SensorConfiguration::SingleEnded),
+            0 => Some(SensorConfiguration::Differential),
+            _ => None
+        }
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ThermocoupleParameters {
+    #[serde(skip_serializing_if = "Option::is_none")]
     cold_junction_channel: Option<LTC2983Channel>,
     sensor_configuration: SensorConfiguration,
     oc_current: LTC2983OcCurrent,
+    #[serde(skip_serializing_if = "Option::is_none")]
     custom_address: Option<u16>
 }
 
-impl Default for ThermocoupleParameters {
-    fn default() -> Self {
-        Self { cold_junction_channel: None,
-               sensor_configuration: Default::default(),
-               oc_current: Default::default(),
-               custom_address: None }
-    }
-}
-
 impl ThermocoupleParameters {
     pub fn cold_junction(mut self, chan: LTC2983Channel) -> Self {
         self.cold_junction_channel = Some(chan);
@@ -98,6 +126,7 @@ impl ThermocoupleParameters {
         self
     }
 
+    /// location of the custom table in the custom data ram (0x250-0x3CF), only used by custom thermocouples
     pub fn custom_address(mut self, addr: u16) -> Self {
         self.custom_address = Some(addr);
         self
@@ -109,25 +138,28 @@ impl ThermocoupleParameters {
     }
 
     pub fn config_to_bits(&self) -> u64 {
-        0x0 | (self.sensor_configuration.identifier() << 3) | self.oc_current.identifier()
+        (self.sensor_configuration.identifier() << 3) | self.oc_current.identifier()
+    }
+
+    pub fn from_config_bits(bits: u64) -> Option<Self> {
+        Some(Self {
+            sensor_configuration: SensorConfiguration::from_identifier((bits >> 3) & 0x1)?,
+            oc_current: LTC2983OcCurrent::from_identifier(bits & 0x7)?,
+            ..Default::default()
+        })
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
 #[allow(non_camel_case_types)]
 pub enum RTDCurve {
+    #[default]
     EuropeanStandard,
     American,
     Japanese,
     ITS_90
 }
 
-impl Default for RTDCurve {
-    fn default() -> Self {
-        Self::EuropeanStandard
-    }
-}
-
 impl RTDCurve {
     pub fn identifier(&self) -> u64 {
         match self {
@@ -137,10 +169,21 @@ impl RTDCurve {
             RTDCurve::ITS_90            => 3,
         }
     }
+
+    pub fn from_identifier(identifier: u64) -> Option<Self> {
+        match identifier {
+            0 => Some(RTDCurve::EuropeanStandard),
+            1 => Some(RTDCurve::American),
+            2 => Some(RTDCurve::Japanese),
+            3 => Some(RTDCurve::ITS_90),
+            _ => None
+        }
+    }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub enum RTDWireCount {
+    #[default]
     Wire2,
     Wire3,
     Wire4,
@@ -156,31 +199,26 @@ impl RTDWireCount {
             RTDWireCount::Wire4KelvinRsense => 3,
         }
     }
-}
 
-impl Default for RTDWireCount {
-    fn default() -> Self {
-        Self::Wire2
+    pub fn from_identifier(identifier: u64) -> Option<Self> {
+        match identifier {
+            0 => Some(RTDWireCount::Wire2),
+            1 => Some(RTDWireCount::Wire3),
+            2 => Some(RTDWireCount::Wire4),
+            3 => Some(RTDWireCount::Wire4KelvinRsense),
+            _ => None
+        }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RTDSensorConfiguration {
     wire_cnt: RTDWireCount,
     external: bool,
     current_source_rotation: bool
 }
 
-impl Default for RTDSensorConfiguration {
-    fn default() -> Self {
-        Self {
-            wire_cnt: Default::default(),
-            external: false,
-            current_source_rotation: false
-        }
-    }
-}
-
 impl RTDSensorConfiguration {
     pub fn wire_cnt(mut self, wire_cnt: RTDWireCount) -> Self { self.wire_cnt = wire_cnt; self }
     pub fn external(mut self, external: bool) -> Self { self.external = external; self }
@@ -190,19 +228,30 @@ impl RTDSensorConfiguration {
         let mut bits = 0x0;
         bits = (bits | self.wire_cnt.identifier()) << 2;
         if self.current_source_rotation && self.wire_cnt != RTDWireCount::Wire2 && self.wire_cnt != RTDWireCount::Wire3 { // current source rotation is not support in 2 or 3 wire RTDs
-            bits = (bits | 0x1) << 1;
+            bits |= 0x2;
         } else {
             if !self.external {
-                bits = bits | 0x1
+                bits |= 0x1
             }
         }
 
         bits
     }
+
+    pub fn from_bits(bits: u64) -> Option<Self> {
+        let wire_cnt = RTDWireCount::from_identifier((bits >> 2) & 0x3)?;
+        match bits & 0x3 {
+            0 => Some(Self { wire_cnt, external: true, current_source_rotation: false }),
+            1 => Some(Self { wire_cnt, external: false, current_source_rotation: false }),
+            2 => Some(Self { wire_cnt, external: false, current_source_rotation: true }),
+            _ => None
+        }
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub enum RTDExcitationCurrent {
+    #[default]
     I5uA,
     I10uA,
     I25uA,
@@ -213,12 +262,6 @@ pub enum RTDExcitationCurrent {
     I1mA
 }
 
-impl Default for RTDExcitationCurrent {
-    fn default() -> Self {
-        Self::I5uA
-    }
-}
-
 impl RTDExcitationCurrent {
     pub fn identifier(&self) -> u64 {
        match self {
@@ -232,14 +275,30 @@ impl RTDExcitationCurrent {
         RTDExcitationCurrent::I1mA   => 8,
     }
     }
+
+    pub fn from_identifier(identifier: u64) -> Option<Self> {
+        match identifier {
+            1 => Some(RTDExcitationCurrent::I5uA),
+            2 => Some(RTDExcitationCurrent::I10uA),
+            3 => Some(RTDExcitationCurrent::I25uA),
+            4 => Some(RTDExcitationCurrent::I50uA),
+            5 => Some(RTDExcitationCurrent::I100uA),
+            6 => Some(RTDExcitationCurrent::I250uA),
+            7 => Some(RTDExcitationCurrent::I500uA),
+            8 => Some(RTDExcitationCurrent::I1mA),
+            _ => None
+        }
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RTDParameters {
     r_sense_channel: LTC2983Channel,
     sensor_configuration: RTDSensorConfiguration,
     excitation_current: RTDExcitationCurrent,
     curve: RTDCurve,
+    #[serde(skip_serializing_if = "Option::is_none")]
     custom_address: Option<u16>
 }
 
@@ -259,6 +318,8 @@ impl RTDParameters {
     pub fn curve(mut self, curve: RTDCurve) -> Self { self.curve = curve; self}
     pub fn excitation_current(mut self, excitation_current: RTDExcitationCurrent) -> Self { self.excitation_current = excitation_current; self }
     pub fn sensor_configuration(mut self, config: RTDSensorConfiguration) -> Self { self.sensor_configuration = config; self }
+    /// location of the custom table in the custom data ram (0x250-0x3CF), only used by custom RTDs
+    pub fn custom_address(mut self, addr: u16) -> Self { self.custom_address = Some(addr); self }
     pub fn channel(mut self, channel: LTC2983Channel) -> Self {
         if channel == LTC2983Channel::CH1 {
             panic!("CH1 can not be used, because there is no channel 0 and the value here indicates that the resistor is between channel x and x-1!!!!")
@@ -269,16 +330,532 @@ impl RTDParameters {
     }
 }
 
-#[derive(Debug)]
-pub enum DiodeReadingCount {
-    READ2,
-    READ3
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum ThermistorExcitationMode {
+    #[default]
+    NoSharingNoRotation,
+    SharingRotation,
+    SharingNoRotation
+}
+
+impl ThermistorExcitationMode {
+    pub fn identifier(&self) -> u64 {
+        match self {
+            ThermistorExcitationMode::NoSharingNoRotation => 0,
+            ThermistorExcitationMode::SharingRotation     => 1,
+            ThermistorExcitationMode::SharingNoRotation   => 2,
+        }
+    }
+
+    pub fn from_identifier(identifier: u64) -> Option<Self> {
+        match identifier {
+            0 => Some(ThermistorExcitationMode::NoSharingNoRotation),
+            1 => Some(ThermistorExcitationMode::SharingRotation),
+            2 => Some(ThermistorExcitationMode::SharingNoRotation),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum ThermistorExcitationCurrent {
+    I250nA,
+    I500nA,
+    I1uA,
+    I5uA,
+    I10uA,
+    I25uA,
+    I50uA,
+    I100uA,
+    I250uA,
+    I500uA,
+    I1mA,
+    #[default]
+    AutoRange
+}
+
+impl ThermistorExcitationCurrent {
+    pub fn identifier(&self) -> u64 {
+        match self {
+            ThermistorExcitationCurrent::I250nA    => 1,
+            ThermistorExcitationCurrent::I500nA    => 2,
+            ThermistorExcitationCurrent::I1uA      => 3,
+            ThermistorExcitationCurrent::I5uA      => 4,
+            ThermistorExcitationCurrent::I10uA     => 5,
+            ThermistorExcitationCurrent::I25uA     => 6,
+            ThermistorExcitationCurrent::I50uA     => 7,
+            ThermistorExcitationCurrent::I100uA    => 8,
+            ThermistorExcitationCurrent::I250uA    => 9,
+            ThermistorExcitationCurrent::I500uA    => 10,
+            ThermistorExcitationCurrent::I1mA      => 11,
+            ThermistorExcitationCurrent::AutoRange => 12,
+        }
+    }
+
+    pub fn from_identifier(identifier: u64) -> Option<Self> {
+        match identifier {
+            1  => Some(ThermistorExcitationCurrent::I250nA),
+            2  => Some(ThermistorExcitationCurrent::I500nA),
+            3  => Some(ThermistorExcitationCurrent::I1uA),
+            4  => Some(ThermistorExcitationCurrent::I5uA),
+            5  => Some(ThermistorExcitationCurrent::I10uA),
+            6  => Some(ThermistorExcitationCurrent::I25uA),
+            7  => Some(ThermistorExcitationCurrent::I50uA),
+            8  => Some(ThermistorExcitationCurrent::I100uA),
+            9  => Some(ThermistorExcitationCurrent::I250uA),
+            10 => Some(ThermistorExcitationCurrent::I500uA),
+            11 => Some(ThermistorExcitationCurrent::I1mA),
+            12 => Some(ThermistorExcitationCurrent::AutoRange),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThermistorParameters {
+    r_sense_channel: LTC2983Channel,
+    sensor_configuration: SensorConfiguration,
+    excitation_mode: ThermistorExcitationMode,
+    excitation_current: ThermistorExcitationCurrent,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    custom_address: Option<u16>
 }
 
-impl Default for DiodeReadingCount {
+impl Default for ThermistorParameters {
     fn default() -> Self {
-        Self::READ2
+        Self {
+            r_sense_channel: LTC2983Channel::CH2,
+            sensor_configuration: Default::default(),
+            excitation_mode: Default::default(),
+            excitation_current: Default::default(),
+            custom_address: None
+        }
+    }
+}
+
+impl ThermistorParameters {
+    pub fn sensor_configuration(mut self, config: SensorConfiguration) -> Self { self.sensor_configuration = config; self }
+    pub fn excitation_mode(mut self, mode: ThermistorExcitationMode) -> Self { self.excitation_mode = mode; self }
+    pub fn excitation_current(mut self, excitation_current: ThermistorExcitationCurrent) -> Self { self.excitation_current = excitation_current; self }
+    /// location of the custom data in the custom data ram (0x250-0x3CF), only used by custom thermistors
+    pub fn custom_address(mut self, addr: u16) -> Self { self.custom_address = Some(addr); self }
+    pub fn channel(mut self, channel: LTC2983Channel) -> Self {
+        if channel == LTC2983Channel::CH1 {
+            panic!("CH1 can not be used, because there is no channel 0 and the value here indicates that the resistor is between channel x and x-1!!!!")
+        } else {
+            self.r_sense_channel = channel;
+            self
+        }
+    }
+
+    pub fn config_to_bits(&self) -> u64 {
+        match self.sensor_configuration {
+            // single ended thermistors are always measured with respect to ground, so sharing and rotation are not available
+            SensorConfiguration::SingleEnded => self.sensor_configuration.identifier() << 2,
+            SensorConfiguration::Differential => (self.sensor_configuration.identifier() << 2) | self.excitation_mode.identifier()
+        }
+    }
+
+    pub fn from_config_bits(bits: u64) -> Option<Self> {
+        Some(Self {
+            sensor_configuration: SensorConfiguration::from_identifier((bits >> 2) & 0x1)?,
+            excitation_mode: ThermistorExcitationMode::from_identifier(bits & 0x3)?,
+            ..Default::default()
+        })
+    }
+}
+
+/// Coefficients of the Steinhart-Hart equation used to linearize custom thermistors
+///
+/// 1/T = A + B*ln(R) + C*ln(R)^2 + D*ln(R)^3 + E*ln(R)^4 + F*ln(R)^5
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SteinhartHartCoefficients {
+    coefficients: [f32; 6]
+}
+
+impl SteinhartHartCoefficients {
+    /// size of the coefficient block in the custom data ram
+    pub const SIZE: u16 = 24;
+
+    pub fn new(a: f32, b: f32, c: f32, d: f32, e: f32, f: f32) -> Self {
+        Self { coefficients: [a, b, c, d, e, f] }
+    }
+
+    /// coefficients are stored as 32 bit IEEE754 floats in big endian byte order
+    pub fn to_bytes(&self) -> [u8; 24] {
+        let mut bytes = [0; 24];
+        for (chunk, coefficient) in bytes.chunks_exact_mut(4).zip(self.coefficients.iter()) {
+            chunk.copy_from_slice(&coefficient.to_be_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8; 24]) -> Self {
+        let mut coefficients = [0.; 6];
+        for (coefficient, chunk) in coefficients.iter_mut().zip(bytes.chunks_exact(4)) {
+            *coefficient = f32::from_be_bytes(chunk.try_into().unwrap());
+        }
+        Self { coefficients }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum CustomTableError {
+    #[error("Custom tables need between 2 and 64 entries, but {0} were given!")]
+    InvalidLength(usize),
+    #[error("Custom table entry {0} is not strictly increasing!")]
+    NotMonotonic(usize),
+    #[error("Custom table entry {0} can not be represented in the fixed point format of the device!")]
+    OutOfRange(usize)
+}
+
+const CUSTOM_TABLE_MIN_LENGTH: usize = 2;
+const CUSTOM_TABLE_MAX_LENGTH: usize = 64;
+
+/// entries of a custom table
+pub type TableEntries = heapless::Vec<(f32, f32), CUSTOM_TABLE_MAX_LENGTH>;
+
+/// custom table or coefficients in the format they are stored in the custom data ram
+pub type CustomData = heapless::Vec<u8, CUSTOM_DATA_SIZE>;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AllocationError {
+    #[error("Not enough custom data ram left to allocate {0} bytes!")]
+    Exhausted(u16),
+    #[error("Custom data at address 0x{0:03X} with size {1} overlaps allocated data or does not fit the custom data ram!")]
+    Unavailable(u16, u16)
+}
+
+/// Allocator handing out non overlapping regions of the custom data ram
+///
+/// Regions are aligned to the 6 byte blocks the custom data pointers address.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CustomDataAllocator {
+    // one bit for every block of the custom data ram
+    used: u64
+}
+
+impl CustomDataAllocator {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// allocate a region for `size` bytes using the first free region that is large enough
+    pub fn allocate(&mut self, size: u16) -> Result<u16, AllocationError> {
+        let blocks = Self::blocks(size);
+        if blocks > 64 {
+            return Err(AllocationError::Exhausted(size));
+        }
+        (0..=64 - blocks)
+            .find(|start| self.used & Self::mask(*start, blocks) == 0)
+            .map(|start| {
+                self.used |= Self::mask(start, blocks);
+                CUSTOM_DATA_START_ADDRESS + start as u16 * CUSTOM_DATA_BLOCK_SIZE
+            })
+            .ok_or(AllocationError::Exhausted(size))
+    }
+
+    /// mark a region that is already in use, e.g. the custom data of a hand configured channel
+    pub fn reserve(&mut self, address: u16, size: u16) -> Result<(), AllocationError> {
+        let start = custom_data_pointer(address, size).ok_or(AllocationError::Unavailable(address, size))? as u32;
+        let mask = Self::mask(start, Self::blocks(size));
+        if self.used & mask != 0 {
+            return Err(AllocationError::Unavailable(address, size));
+        }
+        self.used |= mask;
+        Ok(())
+    }
+
+    pub fn free(&mut self, address: u16, size: u16) {
+        if let Some(start) = custom_data_pointer(address, size) {
+            self.used &= !Self::mask(start as u32, Self::blocks(size));
+        }
+    }
+
+    /// release all regions so the custom data can be packed again from the start of the custom data ram
+    pub fn clear(&mut self) {
+        self.used = 0;
+    }
+
+    /// number of free bytes, these are not necessarily contiguous
+    pub fn available(&self) -> u16 {
+        self.used.count_zeros() as u16 * CUSTOM_DATA_BLOCK_SIZE
+    }
+
+    fn blocks(size: u16) -> u32 {
+        size.div_ceil(CUSTOM_DATA_BLOCK_SIZE).max(1) as u32
+    }
+
+    fn mask(start: u32, blocks: u32) -> u64 {
+        let bits = if blocks >= 64 { u64::MAX } else { (1 << blocks) - 1 };
+        bits.checked_shl(start).unwrap_or(0)
+    }
+}
+
+/// Resistance to temperature table for custom thermistors
+///
+/// Each entry holds the resistance in Ω and the corresponding temperature in K. Entries need to
+/// be ordered by increasing resistance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "TableEntries", into = "TableEntries")]
+pub struct CustomThermistorTable {
+    entries: TableEntries
+}
+
+impl CustomThermistorTable {
+    pub fn new(entries: &[(f32, f32)]) -> Result<Self, CustomTableError> {
+        // the temperature of a thermistor may rise or fall with the resistance so only the resistance has to be ordered
+        let entries = validate_custom_table(entries, false, |resistance, temperature| {
+            to_fixed_u24::<U6>(resistance).is_some() && to_fixed_u24::<U10>(temperature).is_some()
+        })?;
+        Ok(Self { entries })
+    }
+
+    /// decode a table from the format it is stored in the custom data ram
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CustomTableError> {
+        Self::new(&decode_table_entries(bytes, |entry| {
+            (from_fixed_u24::<U6>(u32::from_be_bytes([0, entry[0], entry[1], entry[2]])),
+             from_fixed_u24::<U10>(u32::from_be_bytes([0, entry[3], entry[4], entry[5]])))
+        })?)
+    }
+
+    pub fn entries(&self) -> &[(f32, f32)] {
+        &self.entries
+    }
+
+    /// every entry is stored as a 24 bit unsigned resistance (1/64 Ω resolution) followed by a
+    /// 24 bit unsigned temperature (1/1024 K resolution)
+    pub fn to_bytes(&self) -> CustomData {
+        let mut bytes = CustomData::new();
+        for (resistance, temperature) in &self.entries {
+            push_u24(&mut bytes, to_fixed_u24::<U6>(*resistance).unwrap_or_default());
+            push_u24(&mut bytes, to_fixed_u24::<U10>(*temperature).unwrap_or_default());
+        }
+        bytes
+    }
+}
+
+impl TryFrom<TableEntries> for CustomThermistorTable {
+    type Error = CustomTableError;
+
+    fn try_from(entries: TableEntries) -> Result<Self, Self::Error> {
+        Self::new(&entries)
+    }
+}
+
+impl From<CustomThermistorTable> for TableEntries {
+    fn from(table: CustomThermistorTable) -> Self {
+        table.entries
+    }
+}
+
+/// Voltage to temperature table for custom thermocouples
+///
+/// Each entry holds the thermocouple voltage in mV and the corresponding temperature in K. Both
+/// columns need to be strictly increasing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "TableEntries", into = "TableEntries")]
+pub struct CustomThermocoupleTable {
+    entries: TableEntries
+}
+
+impl CustomThermocoupleTable {
+    pub fn new(entries: &[(f32, f32)]) -> Result<Self, CustomTableError> {
+        let entries = validate_custom_table(entries, true, |voltage, temperature| {
+            to_fixed_i24::<U10>(voltage).is_some() && to_fixed_u24::<U10>(temperature).is_some()
+        })?;
+        Ok(Self { entries })
+    }
+
+    /// decode a table from the format it is stored in the custom data ram
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CustomTableError> {
+        Self::new(&decode_table_entries(bytes, |entry| {
+            (from_fixed_i24::<U10>(u32::from_be_bytes([0, entry[0], entry[1], entry[2]])),
+             from_fixed_u24::<U10>(u32::from_be_bytes([0, entry[3], entry[4], entry[5]])))
+        })?)
+    }
+
+    pub fn entries(&self) -> &[(f32, f32)] {
+        &self.entries
+    }
+
+    /// every entry is stored as a 24 bit signed voltage (1/1024 mV resolution) followed by a
+    /// 24 bit unsigned temperature (1/1024 K resolution)
+    pub fn to_bytes(&self) -> CustomData {
+        let mut bytes = CustomData::new();
+        for (voltage, temperature) in &self.entries {
+            push_u24(&mut bytes, to_fixed_i24::<U10>(*voltage).unwrap_or_default());
+            push_u24(&mut bytes, to_fixed_u24::<U10>(*temperature).unwrap_or_default());
+        }
+        bytes
+    }
+}
+
+impl TryFrom<TableEntries> for CustomThermocoupleTable {
+    type Error = CustomTableError;
+
+    fn try_from(entries: TableEntries) -> Result<Self, Self::Error> {
+        Self::new(&entries)
+    }
+}
+
+impl From<CustomThermocoupleTable> for TableEntries {
+    fn from(table: CustomThermocoupleTable) -> Self {
+        table.entries
+    }
+}
+
+/// Resistance to temperature table for custom RTDs
+///
+/// Each entry holds the resistance in Ω and the corresponding temperature in K. Both columns need
+/// to be strictly increasing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "TableEntries", into = "TableEntries")]
+pub struct CustomRTDTable {
+    entries: TableEntries
+}
+
+impl CustomRTDTable {
+    pub fn new(entries: &[(f32, f32)]) -> Result<Self, CustomTableError> {
+        let entries = validate_custom_table(entries, true, |resistance, temperature| {
+            to_fixed_u24::<U10>(resistance).is_some() && to_fixed_u24::<U10>(temperature).is_some()
+        })?;
+        Ok(Self { entries })
     }
+
+    /// decode a table from the format it is stored in the custom data ram
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CustomTableError> {
+        Self::new(&decode_table_entries(bytes, |entry| {
+            (from_fixed_u24::<U10>(u32::from_be_bytes([0, entry[0], entry[1], entry[2]])),
+             from_fixed_u24::<U10>(u32::from_be_bytes([0, entry[3], entry[4], entry[5]])))
+        })?)
+    }
+
+    pub fn entries(&self) -> &[(f32, f32)] {
+        &self.entries
+    }
+
+    /// every entry is stored as a 24 bit unsigned resistance (1/1024 Ω resolution) followed by a
+    /// 24 bit unsigned temperature (1/1024 K resolution)
+    pub fn to_bytes(&self) -> CustomData {
+        let mut bytes = CustomData::new();
+        for (resistance, temperature) in &self.entries {
+            push_u24(&mut bytes, to_fixed_u24::<U10>(*resistance).unwrap_or_default());
+            push_u24(&mut bytes, to_fixed_u24::<U10>(*temperature).unwrap_or_default());
+        }
+        bytes
+    }
+}
+
+impl TryFrom<TableEntries> for CustomRTDTable {
+    type Error = CustomTableError;
+
+    fn try_from(entries: TableEntries) -> Result<Self, Self::Error> {
+        Self::new(&entries)
+    }
+}
+
+impl From<CustomRTDTable> for TableEntries {
+    fn from(table: CustomRTDTable) -> Self {
+        table.entries
+    }
+}
+
+/// Voltage to value table for direct ADC channels (LTC2986 only)
+///
+/// Each entry holds the input voltage in mV and the value the device reports for it, e.g. the
+/// temperature of an active analog temperature sensor. The voltage column needs to be strictly
+/// increasing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "TableEntries", into = "TableEntries")]
+pub struct CustomADCTable {
+    entries: TableEntries
+}
+
+impl CustomADCTable {
+    pub fn new(entries: &[(f32, f32)]) -> Result<Self, CustomTableError> {
+        let entries = validate_custom_table(entries, false, |voltage, value| {
+            to_fixed_i24::<U10>(voltage).is_some() && to_fixed_i24::<U10>(value).is_some()
+        })?;
+        Ok(Self { entries })
+    }
+
+    /// decode a table from the format it is stored in the custom data ram
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CustomTableError> {
+        Self::new(&decode_table_entries(bytes, |entry| {
+            (from_fixed_i24::<U10>(u32::from_be_bytes([0, entry[0], entry[1], entry[2]])),
+             from_fixed_i24::<U10>(u32::from_be_bytes([0, entry[3], entry[4], entry[5]])))
+        })?)
+    }
+
+    pub fn entries(&self) -> &[(f32, f32)] {
+        &self.entries
+    }
+
+    /// every entry is stored as a 24 bit signed voltage (1/1024 mV resolution) followed by a
+    /// 24 bit signed value (1/1024 resolution)
+    pub fn to_bytes(&self) -> CustomData {
+        let mut bytes = CustomData::new();
+        for (voltage, value) in &self.entries {
+            push_u24(&mut bytes, to_fixed_i24::<U10>(*voltage).unwrap_or_default());
+            push_u24(&mut bytes, to_fixed_i24::<U10>(*value).unwrap_or_default());
+        }
+        bytes
+    }
+}
+
+impl TryFrom<TableEntries> for CustomADCTable {
+    type Error = CustomTableError;
+
+    fn try_from(entries: TableEntries) -> Result<Self, Self::Error> {
+        Self::new(&entries)
+    }
+}
+
+impl From<CustomADCTable> for TableEntries {
+    fn from(table: CustomADCTable) -> Self {
+        table.entries
+    }
+}
+
+/// check the length, value range and ordering of a custom table and copy the entries of a valid table
+fn validate_custom_table(entries: &[(f32, f32)], increasing_temperature: bool, representable: impl Fn(f32, f32) -> bool) -> Result<TableEntries, CustomTableError> {
+    if entries.len() < CUSTOM_TABLE_MIN_LENGTH || entries.len() > CUSTOM_TABLE_MAX_LENGTH {
+        return Err(CustomTableError::InvalidLength(entries.len()));
+    }
+    for (i, (value, temperature)) in entries.iter().enumerate() {
+        if !representable(*value, *temperature) {
+            return Err(CustomTableError::OutOfRange(i));
+        }
+        if i > 0 && (*value <= entries[i-1].0 || (increasing_temperature && *temperature <= entries[i-1].1)) {
+            return Err(CustomTableError::NotMonotonic(i));
+        }
+    }
+    TableEntries::from_slice(entries).map_err(|_| CustomTableError::InvalidLength(entries.len()))
+}
+
+/// decode the 6 byte entries of a custom table as stored in the custom data ram
+fn decode_table_entries(bytes: &[u8], decode: impl Fn(&[u8]) -> (f32, f32)) -> Result<TableEntries, CustomTableError> {
+    let mut entries = TableEntries::new();
+    for entry in bytes.chunks_exact(6) {
+        entries.push(decode(entry)).map_err(|_| CustomTableError::InvalidLength(bytes.len() / 6))?;
+    }
+    Ok(entries)
+}
+
+/// append the lower 24 bits of a fixed point value to the custom data
+fn push_u24(bytes: &mut CustomData, value: u32) {
+    // tables never exceed the size of the custom data ram, so the capacity is never exhausted
+    let _ = bytes.extend_from_slice(&value.to_be_bytes()[1..]);
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum DiodeReadingCount {
+    #[default]
+    READ2,
+    READ3
 }
 
 impl DiodeReadingCount {
@@ -288,22 +865,25 @@ impl DiodeReadingCount {
             DiodeReadingCount::READ3 => 1,
         }
     }
+
+    pub fn from_identifier(identifier: u64) -> Option<Self> {
+        match identifier {
+            0 => Some(DiodeReadingCount::READ2),
+            1 => Some(DiodeReadingCount::READ3),
+            _ => None
+        }
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub enum DiodeExcitationCurrent {
+    #[default]
     I10uA,
     I20uA,
     I40uA,
     I80uA
 }
 
-impl Default for DiodeExcitationCurrent {
-    fn default() -> Self {
-        Self::I10uA
-    }
-}
-
 impl DiodeExcitationCurrent {
     pub fn identifier(&self) -> u64 {
         match self {
@@ -313,14 +893,26 @@ impl DiodeExcitationCurrent {
             DiodeExcitationCurrent::I80uA => 3,
         }
     }
+
+    pub fn from_identifier(identifier: u64) -> Option<Self> {
+        match identifier {
+            0 => Some(DiodeExcitationCurrent::I10uA),
+            1 => Some(DiodeExcitationCurrent::I20uA),
+            2 => Some(DiodeExcitationCurrent::I40uA),
+            3 => Some(DiodeExcitationCurrent::I80uA),
+            _ => None
+        }
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DiodeParameters {
     sensor_configuration: SensorConfiguration,
     num_reading: DiodeReadingCount,
     avg: bool,
     excitation_current: DiodeExcitationCurrent,
+    #[serde(rename = "ideality_factor", skip_serializing_if = "Option::is_none")]
     idealitiy_factor: Option<f32>
 }
 
@@ -363,7 +955,7 @@ impl DiodeParameters {
     }
 
     pub fn to_bits(&self) -> u64 {
-        0x0 | (self.sensor_configuration.identifier() << 26)
+        (self.sensor_configuration.identifier() << 26)
             | (self.num_reading.identifier() << 25)
             | ((self.avg as u64) << 24)
             | (self.excitation_current.identifier() << 22)
@@ -375,11 +967,37 @@ impl DiodeParameters {
                 }
             })
     }
+
+    pub fn from_bits(bits: u64) -> Option<Self> {
+        Some(Self {
+            sensor_configuration: SensorConfiguration::from_identifier((bits >> 26) & 0x1)?,
+            num_reading: DiodeReadingCount::from_identifier((bits >> 25) & 0x1)?,
+            avg: (bits >> 24) & 0x1 == 0x1,
+            excitation_current: DiodeExcitationCurrent::from_identifier((bits >> 22) & 0x3)?,
+            idealitiy_factor: match bits & 0x3fffff {
+                0 => None,
+                factor => Some(FixedU32::<U20>::from_bits(factor as u32).to_num())
+            }
+        })
+    }
 }
 
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DirectADCParameters {
+    sensor_configuration: SensorConfiguration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    custom_address: Option<u16>
+}
+
+impl DirectADCParameters {
+    pub fn sensor_configuration(mut self, config: SensorConfiguration) -> Self { self.sensor_configuration = config; self }
+    /// location of the custom table in the custom data ram (0x250-0x3CF)
+    pub fn custom_address(mut self, addr: u16) -> Self { self.custom_address = Some(addr); self }
+}
 
 #[allow(non_camel_case_types)]
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum ThermalProbeType {
     Thermocouple_J(ThermocoupleParameters),
     Thermocouple_K(ThermocoupleParameters),
@@ -389,6 +1007,7 @@ pub enum ThermalProbeType {
     Thermocouple_S(ThermocoupleParameters),
     Thermocouple_T(ThermocoupleParameters),
     Thermocouple_B(ThermocoupleParameters),
+    Thermocouple_Custom(ThermocoupleParameters, CustomThermocoupleTable),
     RTD_PT10(RTDParameters),
     RTD_PT50(RTDParameters),
     RTD_PT100(RTDParameters),
@@ -397,15 +1016,21 @@ pub enum ThermalProbeType {
     RTD_PT1000(RTDParameters),
     RTD_1000(RTDParameters),
     RTD_NI120(RTDParameters),
-    Thermistor_44004_44033,
-    Thermistor_44005_44030,
-    Thermistor_44007_44034,
-    Thermistor_44006_44031,
-    Thermistor_44008_44032,
-    Thermistor_YSI400,
-    Thermistor_Spectrum,
+    RTD_Custom(RTDParameters, CustomRTDTable),
+    Thermistor_44004_44033(ThermistorParameters),
+    Thermistor_44005_44030(ThermistorParameters),
+    Thermistor_44007_44034(ThermistorParameters),
+    Thermistor_44006_44031(ThermistorParameters),
+    Thermistor_44008_44032(ThermistorParameters),
+    Thermistor_YSI400(ThermistorParameters),
+    Thermistor_Spectrum(ThermistorParameters),
+    Thermistor_Custom_SteinhartHart(ThermistorParameters, SteinhartHartCoefficients),
+    Thermistor_Custom_Table(ThermistorParameters, CustomThermistorTable),
     Diode(DiodeParameters),
-    SenseResistor(f32)
+    SenseResistor(f32),
+    DirectADC(SensorConfiguration),
+    /// direct adc measurement converted with a custom table, only supported by the LTC2986
+    DirectADC_Custom(DirectADCParameters, CustomADCTable)
 }
 
 impl ThermalProbeType {
@@ -419,6 +1044,7 @@ impl ThermalProbeType {
             ThermalProbeType::Thermocouple_S(_)      => 6,
             ThermalProbeType::Thermocouple_T(_)      => 7,
             ThermalProbeType::Thermocouple_B(_)      => 8,
+            ThermalProbeType::Thermocouple_Custom(_, _) => 9,
             ThermalProbeType::RTD_PT10(_)            => 10,
             ThermalProbeType::RTD_PT50(_)            => 11,
             ThermalProbeType::RTD_PT100(_)           => 12,
@@ -427,41 +1053,297 @@ impl ThermalProbeType {
             ThermalProbeType::RTD_PT1000(_)          => 15,
             ThermalProbeType::RTD_1000(_)            => 16,
             ThermalProbeType::RTD_NI120(_)           => 17,
-            ThermalProbeType::Thermistor_44004_44033 => 19,
-            ThermalProbeType::Thermistor_44005_44030 => 20,
-            ThermalProbeType::Thermistor_44007_44034 => 21,
-            ThermalProbeType::Thermistor_44006_44031 => 22,
-            ThermalProbeType::Thermistor_44008_44032 => 23,
-            ThermalProbeType::Thermistor_YSI400      => 24,
-            ThermalProbeType::Thermistor_Spectrum    => 25,
+            ThermalProbeType::RTD_Custom(_, _)       => 18,
+            ThermalProbeType::Thermistor_44004_44033(_) => 19,
+            ThermalProbeType::Thermistor_44005_44030(_) => 20,
+            ThermalProbeType::Thermistor_44007_44034(_) => 21,
+            ThermalProbeType::Thermistor_44006_44031(_) => 22,
+            ThermalProbeType::Thermistor_44008_44032(_) => 23,
+            ThermalProbeType::Thermistor_YSI400(_)      => 24,
+            ThermalProbeType::Thermistor_Spectrum(_)    => 25,
+            ThermalProbeType::Thermistor_Custom_SteinhartHart(_, _) => 26,
+            ThermalProbeType::Thermistor_Custom_Table(_, _)         => 27,
             ThermalProbeType::Diode(_)               => 28,
-            ThermalProbeType::SenseResistor(_)       => 29
+            ThermalProbeType::SenseResistor(_)       => 29,
+            ThermalProbeType::DirectADC(_)           => 30,
+            ThermalProbeType::DirectADC_Custom(_, _) => 30
+        }
+    }
+
+    /// custom table or coefficients the probe needs in the custom data ram, together with the address they are placed at
+    pub fn custom_data(&self) -> Option<(Option<u16>, CustomData)> {
+        match self {
+            ThermalProbeType::Thermocouple_Custom(param, table) => Some((param.custom_address, table.to_bytes())),
+            ThermalProbeType::RTD_Custom(param, table) => Some((param.custom_address, table.to_bytes())),
+            ThermalProbeType::Thermistor_Custom_SteinhartHart(param, coefficients) => Some((param.custom_address, CustomData::from_slice(&coefficients.to_bytes()).unwrap_or_default())),
+            ThermalProbeType::Thermistor_Custom_Table(param, table) => Some((param.custom_address, table.to_bytes())),
+            ThermalProbeType::DirectADC_Custom(param, table) => Some((param.custom_address, table.to_bytes())),
+            _ => None
+        }
+    }
+
+    fn set_custom_address(&mut self, address: u16) {
+        match self {
+            ThermalProbeType::Thermocouple_Custom(param, _) => param.custom_address = Some(address),
+            ThermalProbeType::RTD_Custom(param, _) => param.custom_address = Some(address),
+            ThermalProbeType::Thermistor_Custom_SteinhartHart(param, _) |
+            ThermalProbeType::Thermistor_Custom_Table(param, _) => param.custom_address = Some(address),
+            ThermalProbeType::DirectADC_Custom(param, _) => param.custom_address = Some(address),
+            _ => {}
+        }
+    }
+
+    /// differential sensors are measured between their channel and the channel below
+    pub fn is_differential(&self) -> bool {
+        match self {
+            ThermalProbeType::Thermocouple_J(param) |
+            ThermalProbeType::Thermocouple_K(param) |
+            ThermalProbeType::Thermocouple_E(param) |
+            ThermalProbeType::Thermocouple_N(param) |
+            ThermalProbeType::Thermocouple_R(param) |
+            ThermalProbeType::Thermocouple_S(param) |
+            ThermalProbeType::Thermocouple_T(param) |
+            ThermalProbeType::Thermocouple_B(param) |
+            ThermalProbeType::Thermocouple_Custom(param, _) => param.sensor_configuration == SensorConfiguration::Differential,
+            ThermalProbeType::RTD_PT10(_)   |
+            ThermalProbeType::RTD_PT50(_)   |
+            ThermalProbeType::RTD_PT100(_)  |
+            ThermalProbeType::RTD_PT200(_)  |
+            ThermalProbeType::RTD_PT500(_)  |
+            ThermalProbeType::RTD_PT1000(_) |
+            ThermalProbeType::RTD_1000(_)   |
+            ThermalProbeType::RTD_NI120(_)  |
+            ThermalProbeType::RTD_Custom(_, _) => true,
+            ThermalProbeType::Thermistor_44004_44033(param) |
+            ThermalProbeType::Thermistor_44005_44030(param) |
+            ThermalProbeType::Thermistor_44007_44034(param) |
+            ThermalProbeType::Thermistor_44006_44031(param) |
+            ThermalProbeType::Thermistor_44008_44032(param) |
+            ThermalProbeType::Thermistor_YSI400(param)      |
+            ThermalProbeType::Thermistor_Spectrum(param)    |
+            ThermalProbeType::Thermistor_Custom_SteinhartHart(param, _) |
+            ThermalProbeType::Thermistor_Custom_Table(param, _) => param.sensor_configuration == SensorConfiguration::Differential,
+            ThermalProbeType::Diode(param) => param.sensor_configuration == SensorConfiguration::Differential,
+            ThermalProbeType::SenseResistor(_) => true,
+            ThermalProbeType::DirectADC(config) => *config == SensorConfiguration::Differential,
+            ThermalProbeType::DirectADC_Custom(param, _) => param.sensor_configuration == SensorConfiguration::Differential
+        }
+    }
+
+    /// channel of the sense resistor RTDs and thermistors are measured against
+    pub fn r_sense_channel(&self) -> Option<LTC2983Channel> {
+        match self {
+            ThermalProbeType::RTD_PT10(param)   |
+            ThermalProbeType::RTD_PT50(param)   |
+            ThermalProbeType::RTD_PT100(param)  |
+            ThermalProbeType::RTD_PT200(param)  |
+            ThermalProbeType::RTD_PT500(param)  |
+            ThermalProbeType::RTD_PT1000(param) |
+            ThermalProbeType::RTD_1000(param)   |
+            ThermalProbeType::RTD_NI120(param)  |
+            ThermalProbeType::RTD_Custom(param, _) => Some(param.r_sense_channel),
+            ThermalProbeType::Thermistor_44004_44033(param) |
+            ThermalProbeType::Thermistor_44005_44030(param) |
+            ThermalProbeType::Thermistor_44007_44034(param) |
+            ThermalProbeType::Thermistor_44006_44031(param) |
+            ThermalProbeType::Thermistor_44008_44032(param) |
+            ThermalProbeType::Thermistor_YSI400(param)      |
+            ThermalProbeType::Thermistor_Spectrum(param)    |
+            ThermalProbeType::Thermistor_Custom_SteinhartHart(param, _) |
+            ThermalProbeType::Thermistor_Custom_Table(param, _) => Some(param.r_sense_channel),
+            _ => None
+        }
+    }
+
+    /// channel of the sensor measuring the cold junction of thermocouples
+    pub fn cold_junction_channel(&self) -> Option<LTC2983Channel> {
+        match self {
+            ThermalProbeType::Thermocouple_J(param) |
+            ThermalProbeType::Thermocouple_K(param) |
+            ThermalProbeType::Thermocouple_E(param) |
+            ThermalProbeType::Thermocouple_N(param) |
+            ThermalProbeType::Thermocouple_R(param) |
+            ThermalProbeType::Thermocouple_S(param) |
+            ThermalProbeType::Thermocouple_T(param) |
+            ThermalProbeType::Thermocouple_B(param) |
+            ThermalProbeType::Thermocouple_Custom(param, _) => param.cold_junction_channel,
+            _ => None
+        }
+    }
+
+    /// decode a 32 bit channel assignment word, `custom_data` has to hold the custom table or
+    /// coefficients the word references for custom sensor types
+    ///
+    /// returns `None` for unassigned channels and words that do not describe a valid configuration
+    pub fn from_bits(word: u32, custom_data: &[u8]) -> Option<Self> {
+        let sensor_type = word >> 27;
+        let bits = word as u64;
+        let custom_address = custom_data_location(word).map(|(address, _)| address);
+        match sensor_type {
+            1..=9 => {
+                // |26-22| Cold Junction Channel ID, |21-18| Sensor Configuration
+                let mut param = ThermocoupleParameters::from_config_bits((bits >> 18) & 0xf)?;
+                param.cold_junction_channel = LTC2983Channel::from_identifier((bits >> 22) & 0x1f);
+                match sensor_type {
+                    1 => Some(ThermalProbeType::Thermocouple_J(param)),
+                    2 => Some(ThermalProbeType::Thermocouple_K(param)),
+                    3 => Some(ThermalProbeType::Thermocouple_E(param)),
+                    4 => Some(ThermalProbeType::Thermocouple_N(param)),
+                    5 => Some(ThermalProbeType::Thermocouple_R(param)),
+                    6 => Some(ThermalProbeType::Thermocouple_S(param)),
+                    7 => Some(ThermalProbeType::Thermocouple_T(param)),
+                    8 => Some(ThermalProbeType::Thermocouple_B(param)),
+                    _ => {
+                        param.custom_address = Some(custom_address?);
+                        Some(ThermalProbeType::Thermocouple_Custom(param, CustomThermocoupleTable::from_bytes(custom_data).ok()?))
+                    }
+                }
+            }
+            10..=18 => {
+                // |26-22| Rsense Channel, |21-18| Sensor Configuration, |17-14| Excitation Current, |13-12| Curve
+                let mut param = RTDParameters {
+                    r_sense_channel: LTC2983Channel::from_identifier((bits >> 22) & 0x1f)?,
+                    sensor_configuration: RTDSensorConfiguration::from_bits((bits >> 18) & 0xf)?,
+                    excitation_current: RTDExcitationCurrent::from_identifier((bits >> 14) & 0xf)?,
+                    curve: RTDCurve::from_identifier((bits >> 12) & 0x3)?,
+                    custom_address: None
+                };
+                match sensor_type {
+                    10 => Some(ThermalProbeType::RTD_PT10(param)),
+                    11 => Some(ThermalProbeType::RTD_PT50(param)),
+                    12 => Some(ThermalProbeType::RTD_PT100(param)),
+                    13 => Some(ThermalProbeType::RTD_PT200(param)),
+                    14 => Some(ThermalProbeType::RTD_PT500(param)),
+                    15 => Some(ThermalProbeType::RTD_PT1000(param)),
+                    16 => Some(ThermalProbeType::RTD_1000(param)),
+                    17 => Some(ThermalProbeType::RTD_NI120(param)),
+                    _ => {
+                        param.custom_address = Some(custom_address?);
+                        Some(ThermalProbeType::RTD_Custom(param, CustomRTDTable::from_bytes(custom_data).ok()?))
+                    }
+                }
+            }
+            19..=27 => {
+                // |26-22| Rsense Channel, |21-19| Sensor Configuration, |18-15| Excitation Current
+                let mut param = ThermistorParameters::from_config_bits((bits >> 19) & 0x7)?;
+                param.r_sense_channel = LTC2983Channel::from_identifier((bits >> 22) & 0x1f)?;
+                param.excitation_current = ThermistorExcitationCurrent::from_identifier((bits >> 15) & 0xf)?;
+                match sensor_type {
+                    19 => Some(ThermalProbeType::Thermistor_44004_44033(param)),
+                    20 => Some(ThermalProbeType::Thermistor_44005_44030(param)),
+                    21 => Some(ThermalProbeType::Thermistor_44007_44034(param)),
+                    22 => Some(ThermalProbeType::Thermistor_44006_44031(param)),
+                    23 => Some(ThermalProbeType::Thermistor_44008_44032(param)),
+                    24 => Some(ThermalProbeType::Thermistor_YSI400(param)),
+                    25 => Some(ThermalProbeType::Thermistor_Spectrum(param)),
+                    26 => {
+                        param.custom_address = Some(custom_address?);
+                        let coefficients = SteinhartHartCoefficients::from_bytes(custom_data.try_into().ok()?);
+                        Some(ThermalProbeType::Thermistor_Custom_SteinhartHart(param, coefficients))
+                    }
+                    _ => {
+                        param.custom_address = Some(custom_address?);
+                        Some(ThermalProbeType::Thermistor_Custom_Table(param, CustomThermistorTable::from_bytes(custom_data).ok()?))
+                    }
+                }
+            }
+            28 => Some(ThermalProbeType::Diode(DiodeParameters::from_bits(bits & 0x7ffffff)?)),
+            // |26-0| Fixed Point Floating point (17,10) no sign bit representing the resistance
+            29 => Some(ThermalProbeType::SenseResistor(FixedU32::<U10>::from_bits(word & 0x7ffffff).to_num())),
+            30 => {
+                // |26| Single Ended, |12| Custom Table
+                let sensor_configuration = SensorConfiguration::from_identifier((bits >> 26) & 0x1)?;
+                if (bits >> 12) & 0x1 == 0 {
+                    Some(ThermalProbeType::DirectADC(sensor_configuration))
+                } else {
+                    let param = DirectADCParameters { sensor_configuration, custom_address: Some(custom_address?) };
+                    Some(ThermalProbeType::DirectADC_Custom(param, CustomADCTable::from_bytes(custom_data).ok()?))
+                }
+            }
+            _ => None
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+bitflags! {
+    /// Fault flags reported in the upper byte of every conversion result
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct FaultFlags: u8 {
+        const SENSOR_HARD_FAULT    = 0x80;
+        const ADC_HARD_FAULT       = 0x40;
+        const CJ_HARD_FAULT        = 0x20;
+        const CJ_SOFT_FAULT        = 0x10;
+        const SENSOR_OVER_RANGE    = 0x08;
+        const SENSOR_UNDER_RANGE   = 0x04;
+        const ADC_OUT_OF_RANGE     = 0x02;
+        const VALID                = 0x01;
+    }
+}
+
+impl FaultFlags {
+    /// faults that make the conversion result unusable
+    pub const HARD_FAULTS: Self = Self::SENSOR_HARD_FAULT.union(Self::ADC_HARD_FAULT).union(Self::CJ_HARD_FAULT);
+
+    pub fn is_hard_fault(&self) -> bool {
+        self.intersects(Self::HARD_FAULTS)
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
 pub enum LTC2983Result {
-    Invalid(u8),
-    Suspect(f32, u8),
+    Invalid(FaultFlags),
+    Suspect(f32, FaultFlags),
     Valid(f32)
 }
 
+impl LTC2983Result {
+    /// decode the result of a direct adc channel, the value holds the measured voltage in V
+    pub fn from_voltage_bytes(bytes: [u8; 4]) -> Self {
+        let value = FixedI32::<U21>::from_be_bytes(reformat_fixedf24_to_fixed_f32(bytes[1..=3].try_into().unwrap()));
+        Self::from_error_code(bytes[0], value.to_num())
+    }
+
+    fn from_error_code(error_code: u8, value: f32) -> Self {
+        let faults = FaultFlags::from_bits_retain(error_code);
+        if faults == FaultFlags::VALID { // only the valid bit is set
+            LTC2983Result::Valid(value)
+        } else if faults.is_hard_fault() { // any hard fault makes the result invalid
+            LTC2983Result::Invalid(faults)
+        } else { // in all other cases the reading should regarded as suspect
+            LTC2983Result::Suspect(value, faults)
+        }
+    }
+}
+
 impl From<[u8; 4]> for LTC2983Result {
     fn from(bytes: [u8; 4]) -> Self {
         let value = FixedI32::<U10>::from_be_bytes(reformat_fixedf24_to_fixed_f32(bytes[1..=3].try_into().unwrap()));
-        let error_code = bytes[0];
-        if error_code == 0x01 { // indicates valid result
-            LTC2983Result::Valid(value.to_num())
-        } else if error_code & 0xe != 0 { //if any of the upper three bits of the error code are set then the result is invalid
-            LTC2983Result::Invalid(error_code)
-        } else { // in all other cases the reading should regarded as suspect
-            LTC2983Result::Suspect(value.to_num(), error_code)
-        }
+        Self::from_error_code(bytes[0], value.to_num())
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+/// Result of a temperature channel labelled with the unit the device reported it in
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemperatureReading {
+    result: LTC2983Result,
+    unit: TemperatureUnit
+}
+
+impl TemperatureReading {
+    pub fn new(result: LTC2983Result, unit: TemperatureUnit) -> Self {
+        TemperatureReading { result, unit }
+    }
+
+    pub fn result(&self) -> LTC2983Result {
+        self.result
+    }
+
+    pub fn unit(&self) -> TemperatureUnit {
+        self.unit
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum LTC2983Channel {
     CH1,
     CH2,
@@ -561,6 +1443,32 @@ impl LTC2983Channel {
         }
     }
 
+    pub fn from_identifier(identifier: u64) -> Option<Self> {
+        match identifier {
+            1  => Some(LTC2983Channel::CH1),
+            2  => Some(LTC2983Channel::CH2),
+            3  => Some(LTC2983Channel::CH3),
+            4  => Some(LTC2983Channel::CH4),
+            5  => Some(LTC2983Channel::CH5),
+            6  => Some(LTC2983Channel::CH6),
+            7  => Some(LTC2983Channel::CH7),
+            8  => Some(LTC2983Channel::CH8),
+            9  => Some(LTC2983Channel::CH9),
+            10 => Some(LTC2983Channel::CH10),
+            11 => Some(LTC2983Channel::CH11),
+            12 => Some(LTC2983Channel::CH12),
+            13 => Some(LTC2983Channel::CH13),
+            14 => Some(LTC2983Channel::CH14),
+            15 => Some(LTC2983Channel::CH15),
+            16 => Some(LTC2983Channel::CH16),
+            17 => Some(LTC2983Channel::CH17),
+            18 => Some(LTC2983Channel::CH18),
+            19 => Some(LTC2983Channel::CH19),
+            20 => Some(LTC2983Channel::CH20),
+            _ => None
+        }
+    }
+
     pub fn mask(&self) -> u32 {
        0x1 << (self.identifier() - 1)
     }
@@ -576,6 +1484,10 @@ pub struct LTC2983Status {
 }
 
 impl LTC2983Status {
+    pub fn start(&self) -> bool {
+        self.start
+    }
+
     pub fn done(&self) -> bool {
         self.done
     }
@@ -591,21 +1503,16 @@ impl From<u8> for LTC2983Status {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub enum LTC2983OcCurrent {
     External,
+    #[default]
     I10uA,
     I100uA,
     I500uA,
     I1mA
 }
 
-impl Default for LTC2983OcCurrent {
-    fn default() -> Self {
-        Self::I10uA
-    }
-}
-
 impl LTC2983OcCurrent {
     pub fn identifier(&self) -> u64 {
         match self {
@@ -616,6 +1523,91 @@ impl LTC2983OcCurrent {
             LTC2983OcCurrent::I1mA => 7,
         }
     }
+
+    pub fn from_identifier(identifier: u64) -> Option<Self> {
+        match identifier {
+            0 => Some(LTC2983OcCurrent::External),
+            4 => Some(LTC2983OcCurrent::I10uA),
+            5 => Some(LTC2983OcCurrent::I100uA),
+            6 => Some(LTC2983OcCurrent::I500uA),
+            7 => Some(LTC2983OcCurrent::I1mA),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit
+}
+
+impl TemperatureUnit {
+    pub fn identifier(&self) -> u8 {
+        match self {
+            TemperatureUnit::Celsius    => 0,
+            TemperatureUnit::Fahrenheit => 1,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum FilterNotch {
+    #[default]
+    Reject50Hz60Hz,
+    Reject60Hz,
+    Reject50Hz
+}
+
+impl FilterNotch {
+    pub fn identifier(&self) -> u8 {
+        match self {
+            FilterNotch::Reject50Hz60Hz => 0,
+            FilterNotch::Reject60Hz     => 1,
+            FilterNotch::Reject50Hz     => 2,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GlobalConfig {
+    temperature_unit: TemperatureUnit,
+    filter_notch: FilterNotch
+}
+
+impl GlobalConfig {
+    pub fn temperature_unit(mut self, unit: TemperatureUnit) -> Self { self.temperature_unit = unit; self }
+    pub fn filter_notch(mut self, notch: FilterNotch) -> Self { self.filter_notch = notch; self }
+
+    pub fn unit(&self) -> TemperatureUnit {
+        self.temperature_unit
+    }
+
+    pub fn notch(&self) -> FilterNotch {
+        self.filter_notch
+    }
+
+    pub fn to_bits(&self) -> u8 {
+        // |7-3| Reserved => equals 0
+        // |2| Temperature Unit
+        // |1-0| Filter Notch
+        (self.temperature_unit.identifier() << 2) | self.filter_notch.identifier()
+    }
+}
+
+impl From<u8> for GlobalConfig {
+    fn from(data: u8) -> Self {
+        GlobalConfig {
+            temperature_unit: if data & 0x4 == 0x4 { TemperatureUnit::Fahrenheit } else { TemperatureUnit::Celsius },
+            filter_notch: match data & 0x3 {
+                1 => FilterNotch::Reject60Hz,
+                2 => FilterNotch::Reject50Hz,
+                _ => FilterNotch::Reject50Hz60Hz
+            }
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -625,41 +1617,109 @@ pub enum LTC2983Error<SPI> {
     #[error("Channel {0:?} not configured!")]
     ChannelUnconfigured(LTC2983Channel),
     #[error("Error while calculating average from mutliple rounds of readouts.")]
-    AvgCalculationError
+    AvgCalculationError,
+    #[error("Channel {0:?} requires custom data but no custom address is set!")]
+    CustomAddressMissing(LTC2983Channel),
+    #[error("Custom data at address 0x{0:03X} with size {1} does not fit the custom data ram!")]
+    InvalidCustomAddress(u16, u16),
+    #[error("Mux configuration delay of {0}µs exceeds the maximum of 25500µs!")]
+    InvalidMuxDelay(u32),
+    #[error("Channel {0:?} holds the invalid configuration 0x{1:08X}!")]
+    InvalidChannelConfig(LTC2983Channel, u32),
+    #[error("EEPROM operation failed with status 0x{0:02X}!")]
+    EepromError(u8),
+    #[error("Channel {0:?} is not available on this device!")]
+    ChannelNotSupported(LTC2983Channel),
+    #[error("Sensor type {1} on channel {0:?} is not supported by this device!")]
+    SensorNotSupported(LTC2983Channel, u64),
+    #[error("Could not read the INTERRUPT pin!")]
+    InterruptPinError,
+    #[error("Conversion did not finish in time!")]
+    ConversionTimeout,
+    #[error("Device did not report ready in time!")]
+    DeviceNotReady,
+    #[error("No valid conversion result after {0} retries!")]
+    RetriesExhausted(u32)
 }
 
-pub struct LTC2983<SPI> {
-    spi_device: SPI,
+#[derive(Debug, Copy, Clone, PartialEq, Error)]
+pub enum ValidationError {
+    #[error("Channel {channel:?} references {r_sense_channel:?} as sense resistor, but it holds no sense resistor!")]
+    SenseResistorMissing { channel: LTC2983Channel, r_sense_channel: LTC2983Channel },
+    #[error("Channel {channel:?} uses {cold_junction_channel:?} as cold junction, but it holds no diode, RTD or thermistor!")]
+    InvalidColdJunction { channel: LTC2983Channel, cold_junction_channel: LTC2983Channel },
+    #[error("Channel {0:?} is differential but there is no channel below it!")]
+    NoNegativeInput(LTC2983Channel),
+    #[error("Channels {channel:?} and {other:?} both claim the input {input:?}!")]
+    InputConflict { channel: LTC2983Channel, other: LTC2983Channel, input: LTC2983Channel },
+    #[error("Channel {channel:?} uses current source rotation, which is not supported with {wire_cnt:?}!")]
+    RotationNotSupported { channel: LTC2983Channel, wire_cnt: RTDWireCount },
+    #[error("Channel {0:?} is a single ended thermistor, which only supports excitation without sharing and rotation!")]
+    ExcitationModeNotSupported(LTC2983Channel),
+    #[error("The custom data of channel {0:?} overlaps other custom data or does not fit the custom data ram!")]
+    CustomDataConflict(LTC2983Channel)
 }
 
-impl<SPI> LTC2983<SPI> where SPI: SpiDevice, SPI::Bus: SpiBus {
-    pub fn new(spi_device: SPI) -> Self {
-        LTC2983 { spi_device }
-    }
-
-    //read device satatus
-    pub fn status(&mut self) -> Result<LTC2983Status, LTC2983Error<SPI::Error>> {
-        let mut read_status_bytes = ByteBuffer::new();
-        read_status_bytes.write_u8(LTC2983_READ);
-        read_status_bytes.write_u16(STATUS_REGISTER);
-        read_status_bytes.write_u8(0x0); //Dummy Data
-
-        let mut recv: [u8; 4] = [0, 0, 0, 0];
-        match self.spi_device.transfer(&mut recv, read_status_bytes.as_bytes()) {
-            Ok(_) => {
-                Ok(LTC2983Status::from(recv[3]))
+// every channel can cause at most 5 validation errors
+const MAX_VALIDATION_ERRORS: usize = 5 * 20;
+
+/// problems found in a channel plan
+pub type ValidationErrors = heapless::Vec<ValidationError, MAX_VALIDATION_ERRORS>;
+
+/// check a full channel plan (index 0 holding CH1) for inconsistent channel assignments
+///
+/// returns every problem found, an empty list means the plan is consistent
+pub fn validate_channel_plan(plan: &[Option<ThermalProbeType>; 20]) -> ValidationErrors {
+    let mut errors = ValidationErrors::new();
+    // the capacity covers the worst case, so no error is ever dropped
+    let mut report = |error| { let _ = errors.push(error); };
+    // the channel that claimed each input
+    let mut claimed: [Option<LTC2983Channel>; 20] = [None; 20];
+    let mut custom_data = CustomDataAllocator::new();
+
+    for (index, probe) in plan.iter().enumerate() {
+        let probe = match probe {
+            Some(probe) => probe,
+            None => continue
+        };
+        let channel = LTC2983Channel::from_identifier(index as u64 + 1).unwrap();
+        let probe_at = |chan: &LTC2983Channel| plan[chan.identifier() as usize - 1].as_ref();
+
+        // check the inputs the sensor is connected to
+        let mut negative = None;
+        if probe.is_differential() {
+            negative = LTC2983Channel::from_identifier(channel.identifier() - 1);
+            if negative.is_none() {
+                report(ValidationError::NoNegativeInput(channel));
+            }
+        }
+        for input in core::iter::once(channel).chain(negative) {
+            let claim = &mut claimed[input.identifier() as usize - 1];
+            match claim {
+                Some(other) => {
+                    // resistive sensors and the sense resistor they reference are connected in series and share
+                    // nodes, with each other and with further sensors excited through the same sense resistor
+                    let other_r_sense = probe_at(other).and_then(|other| other.r_sense_channel());
+                    let in_series = probe.r_sense_channel() == Some(*other)
+                        || other_r_sense == Some(channel)
+                        || (other_r_sense.is_some() && other_r_sense == probe.r_sense_channel());
+                    if !in_series {
+                        report(ValidationError::InputConflict { channel, other: *other, input });
+                    }
+                }
+                None => *claim = Some(channel)
             }
-            Err(err) => Err(LTC2983Error::SpiError(err))
         }
 
-    }
+        // check that the custom data does not overlap the custom data of other channels
+        if let Some((Some(address), data)) = probe.custom_data() {
+            if custom_data.reserve(address, data.len() as u16).is_err() {
+                report(ValidationError::CustomDataConflict(channel));
+            }
+        }
 
-    //write channel configuration
-    pub fn setup_channel(&mut self,
-                         probe: ThermalProbeType,
-                         channel: &LTC2983Channel) -> Result<(), LTC2983Error<SPI::Error>>
-    {
-        match &probe {
+        // check the channels referenced by the sensor
+        match probe {
             ThermalProbeType::Thermocouple_J(param) |
             ThermalProbeType::Thermocouple_K(param) |
             ThermalProbeType::Thermocouple_E(param) |
@@ -667,24 +1727,17 @@ impl<SPI> LTC2983<SPI> where SPI: SpiDevice, SPI::Bus: SpiBus {
             ThermalProbeType::Thermocouple_R(param) |
             ThermalProbeType::Thermocouple_S(param) |
             ThermalProbeType::Thermocouple_T(param) |
-            ThermalProbeType::Thermocouple_B(param) => {
-                let mut write_sequence = ByteBuffer::new();
-                write_sequence.write_u8(LTC2983_WRITE);              //the first byte of the communication indicates a read or write operation
-                write_sequence.write_u16(channel.start_address());   //the second two bytes hold the address to ẁrite to
-                // The 32 bit data to be written to the channel configuration register has the following format for thermocouples
-                // |31-27| Thermocouple Type
-                write_sequence.write_bits(probe.identifier(), 5);
-                // |26-22| Could Junction Channel ID -> if no cold junction compensation is used this value will be 0
-                write_sequence.write_bits(match &param.cold_junction_channel { None => 0, Some(chan) => chan.identifier() }, 5);
-                // |21-18| Sensor Configuration
-                write_sequence.write_bits(param.config_to_bits(), 4);
-                // |17-12| Unused => equals 0
-                write_sequence.write_bits(0, 6);
-                // |11-0| Custom Thermocouple Data Pointer
-                write_sequence.write_bits(match &param.custom_address { None => 0, Some(addr) => *addr}.into(), 12);
-
-                self.spi_device.write(write_sequence.as_bytes())?;
-                Ok(())
+            ThermalProbeType::Thermocouple_B(param) |
+            ThermalProbeType::Thermocouple_Custom(param, _) => {
+                if let Some(cold_junction_channel) = param.cold_junction_channel {
+                    let valid = match probe_at(&cold_junction_channel) {
+                        Some(cold_junction) => cold_junction_channel != channel && matches!(cold_junction.identifier(), 10..=28),
+                        None => false
+                    };
+                    if !valid {
+                        report(ValidationError::InvalidColdJunction { channel, cold_junction_channel });
+                    }
+                }
             }
             ThermalProbeType::RTD_PT10(param)   |
             ThermalProbeType::RTD_PT50(param)   |
@@ -693,214 +1746,939 @@ impl<SPI> LTC2983<SPI> where SPI: SpiDevice, SPI::Bus: SpiBus {
             ThermalProbeType::RTD_PT500(param)  |
             ThermalProbeType::RTD_PT1000(param) |
             ThermalProbeType::RTD_1000(param)   |
-            ThermalProbeType::RTD_NI120(param)  => {
-                let mut write_sequence = ByteBuffer::new();
-                write_sequence.write_u8(LTC2983_WRITE);              //the first byte of the communication indicates a read or write operation
-                write_sequence.write_u16(channel.start_address());   //the second two bytes hold the address to ẁrite to
-                // The 32 bit data to be written to the channel configuration register has the following format for thermocouples
-                // |31-27| RTD Type
-                write_sequence.write_bits(probe.identifier(), 5);
-                // |26-22| Rsense Channel Assignment
-                write_sequence.write_bits(param.r_sense_channel.identifier(), 5);
-                // |21-18| Sensor Configuration
-                write_sequence.write_bits(param.sensor_configuration.to_bits(), 4);
-                // |17-14| Excitation Current
-                write_sequence.write_bits(param.excitation_current.identifier(), 4);
-                // |13-12| Curve
-                write_sequence.write_bits(param.curve.identifier(), 2);
-                // |11-0| Custom RTD Data Pointer
-                write_sequence.write_bits(match &param.custom_address { None => 0, Some(addr) => *addr}.into(), 12);
-
-                self.spi_device.write(write_sequence.as_bytes())?;
-                Ok(())
-            }
-            ThermalProbeType::Thermistor_44004_44033 |
-            ThermalProbeType::Thermistor_44005_44030 |
-            ThermalProbeType::Thermistor_44007_44034 |
-            ThermalProbeType::Thermistor_44006_44031 |
-            ThermalProbeType::Thermistor_44008_44032 |
-            ThermalProbeType::Thermistor_YSI400      |
-            ThermalProbeType::Thermistor_Spectrum    => {
-                unimplemented!();
+            ThermalProbeType::RTD_NI120(param)  |
+            ThermalProbeType::RTD_Custom(param, _) => {
+                if !matches!(probe_at(&param.r_sense_channel), Some(ThermalProbeType::SenseResistor(_))) {
+                    report(ValidationError::SenseResistorMissing { channel, r_sense_channel: param.r_sense_channel });
+                }
+                let config = &param.sensor_configuration;
+                if config.current_source_rotation && (config.wire_cnt == RTDWireCount::Wire2 || config.wire_cnt == RTDWireCount::Wire3) {
+                    report(ValidationError::RotationNotSupported { channel, wire_cnt: config.wire_cnt });
+                }
             }
-            ThermalProbeType::Diode(param) => {
-                let mut write_sequence = ByteBuffer::new();
-                write_sequence.write_u8(LTC2983_WRITE);              //the first byte of the communication indicates a read or write operation
-                write_sequence.write_u16(channel.start_address());   //the second two bytes hold the address to ẁrite to
-                write_sequence.write_bits(probe.identifier(), 5);
-                write_sequence.write_bits(param.to_bits(), 27);
-
-                self.spi_device.write(write_sequence.as_bytes())?;
-                Ok(())
+            ThermalProbeType::Thermistor_44004_44033(param) |
+            ThermalProbeType::Thermistor_44005_44030(param) |
+            ThermalProbeType::Thermistor_44007_44034(param) |
+            ThermalProbeType::Thermistor_44006_44031(param) |
+            ThermalProbeType::Thermistor_44008_44032(param) |
+            ThermalProbeType::Thermistor_YSI400(param)      |
+            ThermalProbeType::Thermistor_Spectrum(param)    |
+            ThermalProbeType::Thermistor_Custom_SteinhartHart(param, _) |
+            ThermalProbeType::Thermistor_Custom_Table(param, _) => {
+                if !matches!(probe_at(&param.r_sense_channel), Some(ThermalProbeType::SenseResistor(_))) {
+                    report(ValidationError::SenseResistorMissing { channel, r_sense_channel: param.r_sense_channel });
+                }
+                if param.sensor_configuration == SensorConfiguration::SingleEnded && param.excitation_mode != ThermistorExcitationMode::NoSharingNoRotation {
+                    report(ValidationError::ExcitationModeNotSupported(channel));
+                }
             }
-            ThermalProbeType::SenseResistor(resistance) => {
-                let mut write_sequence = ByteBuffer::new();
-                write_sequence.write_u8(LTC2983_WRITE);              //the first byte of the communication indicates a read or write operation
-                write_sequence.write_u16(channel.start_address());   //the second two bytes hold the address to ẁrite to
-                // The 32 bit data to be written to the channel configuration register has the following format for sense resistors
-                // |31-27| Thermocouple Type
-                write_sequence.write_bits(probe.identifier(), 5);
-                // |26-0| Fixed Point Floating point (17,10) no sign bit representing the resistance
-                let resistance_fixed_point = FixedU32::<U10>::from_num(*resistance);
-                write_sequence.write_bits(resistance_fixed_point.to_bits().into(), 27);
-
-                self.spi_device.write(write_sequence.as_bytes())?;
-                Ok(())
+            ThermalProbeType::Diode(_) |
+            ThermalProbeType::SenseResistor(_) |
+            ThermalProbeType::DirectADC(_) |
+            ThermalProbeType::DirectADC_Custom(_, _) => {}
+        }
+    }
+
+    errors
+}
+
+/// A member of the LTC2983 family, selects the functionality the driver offers for the device
+pub trait DeviceVariant {
+    /// number of input channels, the device offers the channels CH1 up to this count
+    const CHANNEL_COUNT: u64;
+
+    /// check if the device is able to measure the probe
+    fn supports(probe: &ThermalProbeType) -> bool;
+}
+
+/// A device variant with an EEPROM that can store the channel configuration
+pub trait EepromVariant: DeviceVariant {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct LTC2983Variant;
+
+impl DeviceVariant for LTC2983Variant {
+    const CHANNEL_COUNT: u64 = 20;
+
+    fn supports(probe: &ThermalProbeType) -> bool {
+        !matches!(probe, ThermalProbeType::DirectADC_Custom(_, _))
+    }
+}
+
+/// The LTC2984 is register compatible with the LTC2983 and adds an EEPROM
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct LTC2984Variant;
+
+impl DeviceVariant for LTC2984Variant {
+    const CHANNEL_COUNT: u64 = 20;
+
+    fn supports(probe: &ThermalProbeType) -> bool {
+        LTC2983Variant::supports(probe)
+    }
+}
+
+impl EepromVariant for LTC2984Variant {}
+
+/// The LTC2986 offers 10 channels, has no diode support and adds custom tables for direct adc channels
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct LTC2986Variant;
+
+impl DeviceVariant for LTC2986Variant {
+    const CHANNEL_COUNT: u64 = 10;
+
+    fn supports(probe: &ThermalProbeType) -> bool {
+        !matches!(probe, ThermalProbeType::Diode(_))
+    }
+}
+
+/// The LTC2986-1 is a LTC2986 with an EEPROM
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct LTC2986_1Variant;
+
+impl DeviceVariant for LTC2986_1Variant {
+    const CHANNEL_COUNT: u64 = LTC2986Variant::CHANNEL_COUNT;
+
+    fn supports(probe: &ThermalProbeType) -> bool {
+        LTC2986Variant::supports(probe)
+    }
+}
+
+impl EepromVariant for LTC2986_1Variant {}
+
+/// Pin type of drivers without an INTERRUPT pin, the end of a conversion is detected by polling the status register
+///
+/// The type has no values, so drivers without a pin never hold one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NoInterruptPin {}
+
+impl digital::ErrorType for NoInterruptPin {
+    type Error = core::convert::Infallible;
+}
+
+impl InputPin for NoInterruptPin {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        match *self {}
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        match *self {}
+    }
+}
+
+impl embedded_hal_async::digital::Wait for NoInterruptPin {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        match *self {}
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        match *self {}
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        match *self {}
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        match *self {}
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        match *self {}
+    }
+}
+
+pub struct LTC2983<SPI, V = LTC2983Variant, INT = NoInterruptPin> {
+    spi_device: SPI,
+    temperature_unit: TemperatureUnit,
+    _variant: V,
+    interrupt: Option<INT>,
+    conversion_timeout_us: u32,
+    max_retries: u32
+}
+
+//reject channels the device variant does not offer
+fn check_channel<V: DeviceVariant, E>(channel: &LTC2983Channel) -> Result<(), LTC2983Error<E>> {
+    if channel.identifier() > V::CHANNEL_COUNT {
+        Err(LTC2983Error::ChannelNotSupported(*channel))
+    } else {
+        Ok(())
+    }
+}
+
+//reject channels and probes the device variant does not offer, including the sense resistor and cold
+//junction channels the probe references
+fn check_probe<V: DeviceVariant, E>(probe: &ThermalProbeType, channel: &LTC2983Channel) -> Result<(), LTC2983Error<E>> {
+    check_channel::<V, E>(channel)?;
+    for referenced in probe.r_sense_channel().iter().chain(probe.cold_junction_channel().iter()) {
+        check_channel::<V, E>(referenced)?;
+    }
+    if V::supports(probe) {
+        Ok(())
+    } else {
+        Err(LTC2983Error::SensorNotSupported(*channel, probe.identifier()))
+    }
+}
+
+//average of the temperatures of `N` channels over multiple rounds, shared by the blocking and the async driver
+struct Average<const N: usize> {
+    sums: [f32; N],
+    rounds: usize,
+    valid_rounds: usize,
+    retries: u32,
+    max_retries: u32
+}
+
+impl<const N: usize> Average<N> {
+    fn new<E>(rounds: usize, max_retries: u32) -> Result<Self, LTC2983Error<E>> {
+        if rounds == 0 {
+            return Err(LTC2983Error::AvgCalculationError);
+        }
+        Ok(Average { sums: [0.; N], rounds, valid_rounds: 0, retries: 0, max_retries })
+    }
+
+    //check if all rounds have been captured
+    fn complete(&self) -> bool {
+        self.valid_rounds >= self.rounds
+    }
+
+    //add the readouts of one round, a round with an invalid result or a failed readout is repeated
+    //until the retries are exhausted
+    fn add<E>(&mut self, readings: [Result<TemperatureReading, LTC2983Error<E>>; N]) -> Result<(), LTC2983Error<E>> {
+        let mut temperatures = [0.; N];
+        for (temperature, reading) in temperatures.iter_mut().zip(readings) {
+            match reading.map(|reading| reading.result()) {
+                Ok(LTC2983Result::Valid(temp)) => *temperature = temp,
+                _ => {
+                    self.retries += 1;
+                    if self.retries > self.max_retries {
+                        return Err(LTC2983Error::RetriesExhausted(self.max_retries));
+                    }
+                    return Ok(());
+                }
             }
         }
+        // do a component wise add of the values
+        for (sum, temperature) in self.sums.iter_mut().zip(temperatures) {
+            *sum += temperature;
+        }
+        self.valid_rounds += 1;
+        Ok(())
+    }
+
+    //calculate average by dividing by the amount of values captured
+    fn result(&self) -> [f32; N] {
+        self.sums.map(|sum| sum / self.rounds as f32)
+    }
+}
+
+impl<SPI> LTC2983<SPI> {
+    pub fn new(spi_device: SPI) -> Self {
+        Self::with_variant(spi_device, LTC2983Variant)
+    }
+}
+
+impl<SPI, V> LTC2983<SPI, V> where V: DeviceVariant {
+    /// create a driver for a specific member of the device family
+    pub fn with_variant(spi_device: SPI, variant: V) -> Self {
+        LTC2983 {
+            spi_device,
+            temperature_unit: Default::default(),
+            _variant: variant,
+            interrupt: None,
+            conversion_timeout_us: DEFAULT_CONVERSION_TIMEOUT_US,
+            max_retries: DEFAULT_MAX_RETRIES
+        }
+    }
+
+    /// use the INTERRUPT output of the device to detect the end of conversions instead of polling the status register
+    pub fn with_interrupt_pin<INT: InputPin>(self, interrupt: INT) -> LTC2983<SPI, V, INT> {
+        LTC2983 {
+            spi_device: self.spi_device,
+            temperature_unit: self.temperature_unit,
+            _variant: self._variant,
+            interrupt: Some(interrupt),
+            conversion_timeout_us: self.conversion_timeout_us,
+            max_retries: self.max_retries
+        }
+    }
+
+    /// time the averaging helpers wait for a single channel conversion before giving up (default 500ms),
+    /// conversions of multiple channels may take this long per channel
+    pub fn set_conversion_timeout(&mut self, timeout_us: u32) {
+        self.conversion_timeout_us = timeout_us;
+    }
+
+    /// number of rounds with invalid results or failed readouts the averaging helpers repeat before giving up (default 3)
+    pub fn set_max_retries(&mut self, max_retries: u32) {
+        self.max_retries = max_retries;
+    }
+}
+
+impl<SPI, V, INT> LTC2983<SPI, V, INT> where SPI: SpiDevice, V: DeviceVariant, INT: InputPin {
+
+    /// unit of the temperatures returned by the device, as last written to or read from the global configuration
+    pub fn temperature_unit(&self) -> TemperatureUnit {
+        self.temperature_unit
+    }
+
+    //write `data` to the memory of the device starting at `address`
+    fn write_memory(&mut self, address: u16, data: &[u8]) -> Result<(), LTC2983Error<SPI::Error>> {
+        let header = frame_header(LTC2983_WRITE, address);
+        self.spi_device.transaction(&mut [Operation::Write(&header), Operation::Write(data)])?;
+        Ok(())
+    }
+
+    //fill `data` from the memory of the device starting at `address`
+    fn read_memory(&mut self, address: u16, data: &mut [u8]) -> Result<(), LTC2983Error<SPI::Error>> {
+        let header = frame_header(LTC2983_READ, address);
+        self.spi_device.transaction(&mut [Operation::Write(&header), Operation::Read(data)])?;
+        Ok(())
+    }
+
+    //read a single byte register
+    fn read_register(&mut self, address: u16) -> Result<u8, LTC2983Error<SPI::Error>> {
+        let mut data = [0];
+        self.read_memory(address, &mut data)?;
+        Ok(data[0])
+    }
+
+    pub fn write_global_config(&mut self, config: &GlobalConfig) -> Result<(), LTC2983Error<SPI::Error>> {
+        self.write_memory(GLOBAL_CONFIG_REGISTER, &[config.to_bits()])?;
+        self.temperature_unit = config.unit();
+        Ok(())
+    }
+
+    pub fn read_global_config(&mut self) -> Result<GlobalConfig, LTC2983Error<SPI::Error>> {
+        let config = GlobalConfig::from(self.read_register(GLOBAL_CONFIG_REGISTER)?);
+        self.temperature_unit = config.unit();
+        Ok(config)
+    }
+
+    //read device satatus
+    pub fn status(&mut self) -> Result<LTC2983Status, LTC2983Error<SPI::Error>> {
+        Ok(LTC2983Status::from(self.read_register(STATUS_REGISTER)?))
+    }
+
+    /// set the additional delay between the conversion phases, the delay is rounded to the 100µs resolution of the device
+    pub fn set_mux_delay(&mut self, delay_us: u32) -> Result<(), LTC2983Error<SPI::Error>> {
+        if delay_us > MUX_CONFIG_DELAY_MAX_US {
+            return Err(LTC2983Error::InvalidMuxDelay(delay_us));
+        }
+        let steps = ((delay_us + MUX_CONFIG_DELAY_STEP_US / 2) / MUX_CONFIG_DELAY_STEP_US) as u8;
+        self.write_memory(MUX_CONFIG_DELAY_REGISTER, &[steps])
+    }
+
+    /// read the additional delay between the conversion phases in µs
+    pub fn mux_delay(&mut self) -> Result<u32, LTC2983Error<SPI::Error>> {
+        Ok(self.read_register(MUX_CONFIG_DELAY_REGISTER)? as u32 * MUX_CONFIG_DELAY_STEP_US)
+    }
+
+    //write channel configuration
+    pub fn setup_channel(&mut self,
+                         probe: ThermalProbeType,
+                         channel: &LTC2983Channel) -> Result<(), LTC2983Error<SPI::Error>>
+    {
+        check_probe::<V, _>(&probe, channel)?;
+        let word = channel_word(&probe, channel)?;
+        // custom sensors need their data to be uploaded before the channel can reference it
+        if let Some((Some(address), data)) = probe.custom_data() {
+            self.write_custom_data(address, &data)?;
+        }
+        self.write_channel_word(channel, word)
+    }
+
+    //write the raw 32 bit channel assignment word
+    fn write_channel_word(&mut self, channel: &LTC2983Channel, word: u32) -> Result<(), LTC2983Error<SPI::Error>> {
+        self.write_memory(channel.start_address(), &word.to_be_bytes())
+    }
+
+    //write a block of data into the custom data ram
+    fn write_custom_data(&mut self, address: u16, data: &[u8]) -> Result<(), LTC2983Error<SPI::Error>> {
+        self.write_memory(address, data)
+    }
+
+    /// read back the configuration of a channel including any custom data it references
+    pub fn read_channel_config(&mut self, channel: &LTC2983Channel) -> Result<ThermalProbeType, LTC2983Error<SPI::Error>> {
+        check_channel::<V, _>(channel)?;
+        let word = self.read_channel_word(channel)?;
+        if word >> 27 == 0 {
+            return Err(LTC2983Error::ChannelUnconfigured(*channel));
+        }
+        let custom_data = match custom_data_location(word) {
+            Some((address, size)) => self.read_custom_data(address, size)?,
+            None => CustomData::new()
+        };
+        ThermalProbeType::from_bits(word, &custom_data).ok_or(LTC2983Error::InvalidChannelConfig(*channel, word))
+    }
+
+    //read the raw 32 bit channel assignment word
+    fn read_channel_word(&mut self, channel: &LTC2983Channel) -> Result<u32, LTC2983Error<SPI::Error>> {
+        let mut word = [0; 4];
+        self.read_memory(channel.start_address(), &mut word)?;
+        Ok(u32::from_be_bytes(word))
+    }
+
+    //read a block of data from the custom data ram
+    fn read_custom_data(&mut self, address: u16, size: u16) -> Result<CustomData, LTC2983Error<SPI::Error>> {
+        let mut data = CustomData::new();
+        data.resize(size as usize, 0).map_err(|_| LTC2983Error::InvalidCustomAddress(address, size))?;
+        self.read_memory(address, &mut data)?;
+        Ok(data)
     }
 
     //check if the channel is configured
     pub fn channel_enabled(&mut self, channel: &LTC2983Channel) -> bool {
-        let mut read_sequence = ByteBuffer::new();
-        read_sequence.write_u8(LTC2983_READ);
-        read_sequence.write_u16(channel.start_address());
-        read_sequence.write_u8(0); //Dummy Data for read
-
-        let mut recv: [u8; 4] = [0, 0, 0, 0];
-        match self.spi_device.transfer(&mut recv, read_sequence.as_bytes()) {
-            Ok(_) => {
-                //if the upper 5bits of the channel are zero, then the channel is disabled so checking for not zero means the channel is enabled
-                if recv[3] & 0xf8 != 0 {
-                    true
-                } else {
-                    false
-                }
-            }
-            Err(_err) => {
-                //on communication error assume unconfigured channel
-                false
-            }
+        if check_channel::<V, SPI::Error>(channel).is_err() {
+            return false;
+        }
+        match self.read_register(channel.start_address()) {
+            //if the upper 5bits of the channel are zero, then the channel is disabled so checking for not zero means the channel is enabled
+            Ok(sensor_type) => sensor_type & 0xf8 != 0,
+            //on communication error assume unconfigured channel
+            Err(_err) => false
         }
     }
 
     pub fn start_conversion(&mut self, channel: &LTC2983Channel) -> Result<(), LTC2983Error<SPI::Error>> {
+        check_channel::<V, _>(channel)?;
         //start measurement
-        let mut start_command_bytes = ByteBuffer::new();
-        start_command_bytes.write_u8(LTC2983_WRITE);
-        start_command_bytes.write_u16(STATUS_REGISTER);
-        start_command_bytes.write_bits(0x4, 3);
-        start_command_bytes.write_bits(channel.identifier(), 5);
+        self.write_memory(STATUS_REGISTER, &[START_CONVERSION_COMMAND | channel.identifier() as u8])
+    }
 
-        self.spi_device.write(start_command_bytes.as_bytes())?;
+    /// put the device into sleep mode, it stays asleep until the next transaction
+    pub fn sleep(&mut self) -> Result<(), LTC2983Error<SPI::Error>> {
+        self.write_memory(STATUS_REGISTER, &[SLEEP_COMMAND])
+    }
+
+    /// wake the device from sleep mode and wait until it has finished its initialization
+    ///
+    /// fails with [`LTC2983Error::DeviceNotReady`] if the device does not report ready within 500ms
+    pub fn wake<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), LTC2983Error<SPI::Error>> {
+        // the first transaction toggles CS which wakes the device
+        self.wait_ready(delay)
+    }
+
+    //wait until the device signals that it is ready by clearing the start bit and setting the done bit
+    fn wait_ready<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), LTC2983Error<SPI::Error>> {
+        let mut waited_us = 0;
+        loop {
+            let status = self.status()?;
+            if !status.start && status.done {
+                return Ok(());
+            }
+            if waited_us >= READY_TIMEOUT_US {
+                return Err(LTC2983Error::DeviceNotReady);
+            }
+            delay.delay_us(DONE_POLL_INTERVAL_US);
+            waited_us += DONE_POLL_INTERVAL_US;
+        }
+    }
 
+    //check the INTERRUPT pin if one was provided, otherwise the status register
+    fn conversion_done(&mut self) -> Result<bool, LTC2983Error<SPI::Error>> {
+        match &mut self.interrupt {
+            // the INTERRUPT output is low while the device is busy
+            Some(interrupt) => interrupt.is_high().map_err(|_| LTC2983Error::InterruptPinError),
+            None => Ok(self.status()?.done)
+        }
+    }
+
+    /// block until the running conversion is done, sleeping on `delay` between the checks
+    ///
+    /// the INTERRUPT pin is checked if one was provided, otherwise the status register is polled. The
+    /// wait has no time limit, use [`LTC2983::wait_done`] to give up on conversions that never finish.
+    pub fn wait_for_completion<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), LTC2983Error<SPI::Error>> {
+        while !self.conversion_done()? {
+            delay.delay_us(DONE_POLL_INTERVAL_US);
+        }
         Ok(())
     }
 
-    pub fn start_multi_conversion(&mut self, channels: &Vec<LTC2983Channel>) -> Result<(), LTC2983Error<SPI::Error>> {
-        let mut write_channel_mask = ByteBuffer::new();
+    /// wait until the running conversion is done or `timeout_us` has passed
+    ///
+    /// the INTERRUPT pin is checked if one was provided, otherwise the status register is polled
+    pub fn wait_done<D: DelayNs>(&mut self, delay: &mut D, timeout_us: u32) -> Result<(), LTC2983Error<SPI::Error>> {
+        let mut waited_us = 0;
+        loop {
+            if self.conversion_done()? {
+                return Ok(());
+            }
+            if waited_us >= timeout_us {
+                return Err(LTC2983Error::ConversionTimeout);
+            }
+            delay.delay_us(DONE_POLL_INTERVAL_US);
+            waited_us += DONE_POLL_INTERVAL_US;
+        }
+    }
+
+    pub fn start_multi_conversion(&mut self, channels: &[LTC2983Channel]) -> Result<(), LTC2983Error<SPI::Error>> {
         let mut mask: u32 = 0x0;
         for chan in channels {
+            check_channel::<V, _>(chan)?;
             mask |= chan.mask();
         }
-        write_channel_mask.write_u8(LTC2983_WRITE);
-        write_channel_mask.write_u16(MULTI_CHANNEL_MASK_REGISTER);
-        write_channel_mask.write_u32(mask);
-        self.spi_device.write(write_channel_mask.as_bytes())?;
+        self.write_memory(MULTI_CHANNEL_MASK_REGISTER, &mask.to_be_bytes())?;
+        // a conversion of channel 0 converts all channels of the multi channel mask
+        self.write_memory(STATUS_REGISTER, &[START_CONVERSION_COMMAND])
+    }
+
+    /// read the result of a temperature channel labelled with the unit of the global configuration
+    ///
+    /// direct adc channels with a custom table report the value looked up in their table, the unit does not apply to it
+    pub fn read_temperature(&mut self, channel: &LTC2983Channel) -> Result<TemperatureReading, LTC2983Error<SPI::Error>> {
+        check_channel::<V, _>(channel)?;
+        let mut result = [0; 4];
+        self.read_memory(channel.result_address(), &mut result)?;
+        Ok(TemperatureReading::new(LTC2983Result::from(result), self.temperature_unit))
+    }
 
-        let mut start_multi_conversion_bytes = ByteBuffer::new();
-        start_multi_conversion_bytes.write_u8(LTC2983_WRITE);
-        start_multi_conversion_bytes.write_u16(STATUS_REGISTER);
-        start_multi_conversion_bytes.write_bits(0x4, 3);
-        start_multi_conversion_bytes.write_bits(0x0, 5);
+    /// read the result of a channel configured as direct adc, the result holds the voltage in V
+    pub fn read_voltage(&mut self, channel: &LTC2983Channel) -> Result<LTC2983Result, LTC2983Error<SPI::Error>> {
+        check_channel::<V, _>(channel)?;
+        let mut result = [0; 4];
+        self.read_memory(channel.result_address(), &mut result)?;
+        Ok(LTC2983Result::from_voltage_bytes(result))
+    }
+
+    pub fn read_multi_temperature<const N: usize>(&mut self, channels: &[LTC2983Channel; N]) -> [Result<TemperatureReading, LTC2983Error<SPI::Error>>; N] {
+        core::array::from_fn(|i| self.read_temperature(&channels[i]))
+    }
 
-        self.spi_device.write(start_multi_conversion_bytes.as_bytes())?;
+    ///do multiple rounds of conversion for a channel then calculate the average of the temperatures read out
+    ///
+    /// the average is in the unit returned by [`LTC2983::temperature_unit`]
+    ///
+    /// rounds with invalid results or failed readouts are repeated up to the configured number of retries
+    pub fn get_temperature_avg<D: DelayNs>(&mut self, channel: &LTC2983Channel, rounds: usize, delay: &mut D) -> Result<f32, LTC2983Error<SPI::Error>> {
+        let mut average = Average::new(rounds, self.max_retries)?;
+        while !average.complete() {
+            self.start_conversion(channel)?;
+            self.wait_done(delay, self.conversion_timeout_us)?;
+            average.add([self.read_temperature(channel)])?;
+        }
+        Ok(average.result()[0])
+    }
+
+    ///do multiple rounds of conversion for multiple channels then calculate the average of the temperatures read out
+    ///
+    /// the averages are in the unit returned by [`LTC2983::temperature_unit`]
+    ///
+    /// rounds with invalid results or failed readouts are repeated up to the configured number of retries
+    pub fn get_multi_temperature_avg<D: DelayNs, const N: usize>(&mut self, channels: &[LTC2983Channel; N], rounds: usize, delay: &mut D) -> Result<[f32; N], LTC2983Error<SPI::Error>> {
+        let mut average = Average::new(rounds, self.max_retries)?;
+        let timeout_us = self.conversion_timeout_us.saturating_mul(N as u32);
+        while !average.complete() {
+            self.start_multi_conversion(channels)?;
+            self.wait_done(delay, timeout_us)?;
+            average.add(self.read_multi_temperature(channels))?;
+        }
+        Ok(average.result())
+    }
+}
+
+impl<SPI, V, INT> LTC2983<SPI, V, INT> where SPI: SpiDevice, V: EepromVariant, INT: InputPin {
+    /// store the channel assignments, custom data and global configuration in the EEPROM,
+    /// the device restores them on its own after every power up
+    pub fn save_to_eeprom<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), LTC2983Error<SPI::Error>> {
+        self.eeprom_command(EEPROM_WRITE_COMMAND, delay)
+    }
+
+    /// overwrite the current configuration with the configuration stored in the EEPROM
+    pub fn restore_from_eeprom<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), LTC2983Error<SPI::Error>> {
+        self.eeprom_command(EEPROM_READ_COMMAND, delay)?;
+        self.read_global_config()?;
         Ok(())
     }
 
-    pub fn read_temperature(&mut self, channel: &LTC2983Channel) -> Result<LTC2983Result, LTC2983Error<SPI::Error>> {
-        let mut read_temperature_bytes = ByteBuffer::new();
-        read_temperature_bytes.write_u8(LTC2983_READ);
-        read_temperature_bytes.write_u16(channel.result_address());
-        read_temperature_bytes.write_u32(0x0); //Dummy bytes for reading
+    //unlock the eeprom, run the command and check the eeprom status once the device is done,
+    //a device that does not finish the command within 500ms fails with `DeviceNotReady`
+    fn eeprom_command<D: DelayNs>(&mut self, command: u8, delay: &mut D) -> Result<(), LTC2983Error<SPI::Error>> {
+        self.write_memory(EEPROM_KEY_REGISTER, &EEPROM_KEY.to_be_bytes())?;
+        self.write_memory(STATUS_REGISTER, &[command])?;
+        self.wait_ready(delay)?;
 
-        let mut recv: [u8; 7] = [0, 0, 0, 0, 0, 0, 0];
-        self.spi_device.transfer(&mut recv, read_temperature_bytes.as_bytes())?;
+        match self.read_register(EEPROM_STATUS_REGISTER)? {
+            0 => Ok(()),
+            status => Err(LTC2983Error::EepromError(status))
+        }
+    }
+}
 
-        Ok(LTC2983Result::from([recv[3], recv[4], recv[5], recv[6]]))
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ConfigChange {
+    GlobalConfig,
+    ChannelAssignment(LTC2983Channel),
+    CustomData(LTC2983Channel)
+}
+
+// the global configuration plus the assignment and custom data of every channel
+const MAX_CONFIG_CHANGES: usize = 1 + 2 * 20;
+
+/// changes between a configuration and the configuration of the device
+pub type ConfigChanges = heapless::Vec<ConfigChange, MAX_CONFIG_CHANGES>;
+
+/// Complete device configuration holding the channel assignments, custom data and global configuration
+///
+/// Channels without a probe are unassigned when the configuration is applied.
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LTC2983Config {
+    #[serde(with = "channel_map")]
+    channels: [Option<ThermalProbeType>; 20],
+    global_config: GlobalConfig
+}
+
+impl LTC2983Config {
+    pub fn channel(mut self, channel: LTC2983Channel, probe: ThermalProbeType) -> Self {
+        self.channels[channel.identifier() as usize - 1] = Some(probe);
+        self
     }
 
-    pub fn read_multi_temperature(&mut self, channels: &Vec<LTC2983Channel>) -> Vec<Result<LTC2983Result, LTC2983Error<SPI::Error>>> {
-        channels.iter().map(|chan| {
-            self.read_temperature(chan)
-        }).collect()
+    pub fn global_config(mut self, config: GlobalConfig) -> Self {
+        self.global_config = config;
+        self
     }
 
-    ///do multiple rounds of conversion for a channel then calculate the average of the temperatures read out
-    pub fn get_temperature_avg(&mut self, channel: &LTC2983Channel, rounds: usize) -> Result<f32, LTC2983Error<SPI::Error>> {
-        let mut values = Vec::new();
-        let mut r = 0;
+    pub fn probe(&self, channel: &LTC2983Channel) -> Option<&ThermalProbeType> {
+        self.channels[channel.identifier() as usize - 1].as_ref()
+    }
 
-        while r < rounds {
-            self.start_conversion(channel)?;
-            while !self.status()?.done {}
-            let mut was_error = false;
-            let mut v: f32 = 0.;
-            match self.read_temperature(channel) {
-                Ok(ltc_res) => {
-                    match ltc_res {
-                        LTC2983Result::Invalid(_) | LTC2983Result::Suspect(_, _) => {
-                            was_error = true;
-                        },
-                        LTC2983Result::Valid(temp) => {
-                            v = temp;
-                        }
-                    }
-                },
-                Err(_err) => {
-                    was_error = true;
-                },
+    pub fn channels(&self) -> &[Option<ThermalProbeType>; 20] {
+        &self.channels
+    }
+
+    pub fn validate(&self) -> ValidationErrors {
+        validate_channel_plan(&self.channels)
+    }
+
+    /// assign non overlapping custom data addresses to all custom probes, packing the custom data
+    /// from the start of the custom data ram in channel order
+    pub fn allocate_custom_data(&mut self) -> Result<(), AllocationError> {
+        let mut allocator = CustomDataAllocator::new();
+        for probe in self.channels.iter_mut().flatten() {
+            if let Some((_, data)) = probe.custom_data() {
+                let address = allocator.allocate(data.len() as u16)?;
+                probe.set_custom_address(address);
+            }
+        }
+        Ok(())
+    }
+
+    /// compare the configuration with the configuration the device currently holds
+    pub fn diff<SPI, V, INT>(&self, device: &mut LTC2983<SPI, V, INT>) -> Result<ConfigChanges, LTC2983Error<SPI::Error>>
+    where SPI: SpiDevice, V: DeviceVariant, INT: InputPin
+    {
+        let mut changes = ConfigChanges::new();
+        // the capacity covers every possible change, so pushing never fails
+        // read the register directly, comparing must not change the unit the driver labels results with
+        if GlobalConfig::from(device.read_register(GLOBAL_CONFIG_REGISTER)?) != self.global_config {
+            let _ = changes.push(ConfigChange::GlobalConfig);
+        }
+        for (index, probe) in self.channels.iter().enumerate() {
+            let channel = LTC2983Channel::from_identifier(index as u64 + 1).unwrap();
+            // channels the device does not offer have to stay unassigned
+            if channel.identifier() > V::CHANNEL_COUNT && probe.is_none() {
+                continue;
+            }
+            let word = match probe {
+                Some(probe) => {
+                    check_probe::<V, _>(probe, &channel)?;
+                    channel_word(probe, &channel)?
+                }
+                None => 0
+            };
+            if device.read_channel_word(&channel)? != word {
+                let _ = changes.push(ConfigChange::ChannelAssignment(channel));
+            }
+            if let Some((Some(address), data)) = probe.as_ref().and_then(|probe| probe.custom_data()) {
+                if device.read_custom_data(address, data.len() as u16)? != data {
+                    let _ = changes.push(ConfigChange::CustomData(channel));
+                }
+            }
+        }
+        Ok(changes)
+    }
+
+    /// write everything that differs from the configuration the device currently holds and return the applied changes
+    pub fn apply<SPI, V, INT>(&self, device: &mut LTC2983<SPI, V, INT>) -> Result<ConfigChanges, LTC2983Error<SPI::Error>>
+    where SPI: SpiDevice, V: DeviceVariant, INT: InputPin
+    {
+        let changes = self.diff(device)?;
+        // upload custom data first so channels never reference stale data
+        for change in &changes {
+            if let ConfigChange::CustomData(channel) = change {
+                if let Some((Some(address), data)) = self.probe(channel).and_then(|probe| probe.custom_data()) {
+                    device.write_custom_data(address, &data)?;
+                }
             }
-            if !was_error {
-                values.push(v);
-                r += 1;
+        }
+        for change in &changes {
+            match change {
+                ConfigChange::GlobalConfig => device.write_global_config(&self.global_config)?,
+                ConfigChange::ChannelAssignment(channel) => {
+                    let word = match self.probe(channel) {
+                        Some(probe) => channel_word(probe, channel)?,
+                        None => 0
+                    };
+                    device.write_channel_word(channel, word)?;
+                }
+                ConfigChange::CustomData(_) => {}
             }
         }
+        Ok(changes)
+    }
+}
+
+/// (de)serialize the channel assignments as a map from channel to probe that only holds the assigned channels
+mod channel_map {
+    use core::fmt;
+
+    use serde::{de::{MapAccess, Visitor}, Deserializer, Serializer};
 
-        values.into_iter().reduce(|acc, e| acc + e).and_then(|v| Some(v / ( rounds as f32))).ok_or(LTC2983Error::AvgCalculationError)
+    use crate::{LTC2983Channel, ThermalProbeType};
+
+    pub fn serialize<S: Serializer>(channels: &[Option<ThermalProbeType>; 20], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(channels.iter().enumerate().filter_map(|(index, probe)| {
+            probe.as_ref().map(|probe| (LTC2983Channel::from_identifier(index as u64 + 1).unwrap(), probe))
+        }))
     }
 
-    ///do multiple rounds of conversion for multiple channels then calculate the average of the temperatures read out
-    pub fn get_multi_temperature_avg(&mut self, channels: &Vec<LTC2983Channel>, rounds: usize) -> Result<Vec<f32>, LTC2983Error<SPI::Error>> {
-        let mut values = Vec::new();
-        let mut r = 0;
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[Option<ThermalProbeType>; 20], D::Error> {
+        deserializer.deserialize_map(ChannelMapVisitor)
+    }
 
-        while r < rounds {
-            self.start_multi_conversion(channels)?;
-            while !self.status()?.done {}
-            let mut v = Vec::new();
-            let mut was_error = false;
-            for res in self.read_multi_temperature(channels) {
-                match res {
-                    Ok(ltc_res) => {
-                        match ltc_res {
-                            LTC2983Result::Invalid(_) | LTC2983Result::Suspect(_, _) => {
-                                was_error = true;
-                            },
-                            LTC2983Result::Valid(temp) => {
-                                v.push(temp);
-                            }
-                        }
-                    },
-                    Err(_err) => {
-                        was_error = true;
-                    },
-                }
+    struct ChannelMapVisitor;
+
+    impl<'de> Visitor<'de> for ChannelMapVisitor {
+        type Value = [Option<ThermalProbeType>; 20];
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map from channel to probe")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut channels: [Option<ThermalProbeType>; 20] = Default::default();
+            while let Some((channel, probe)) = map.next_entry::<LTC2983Channel, ThermalProbeType>()? {
+                channels[channel.identifier() as usize - 1] = Some(probe);
             }
-            if !was_error {
-                values.push(v);
-                r += 1;
+            Ok(channels)
+        }
+    }
+}
+
+/// encode the 32 bit channel assignment word of a probe, custom data is referenced at the custom address of the probe
+fn channel_word<E>(probe: &ThermalProbeType, channel: &LTC2983Channel) -> Result<u32, LTC2983Error<E>> {
+    let custom_data_pointer = match probe.custom_data() {
+        Some((address, data)) => {
+            let address = address.ok_or(LTC2983Error::CustomAddressMissing(*channel))?;
+            let size = data.len() as u16;
+            let pointer = custom_data_pointer(address, size).ok_or(LTC2983Error::InvalidCustomAddress(address, size))?;
+            match probe {
+                // |11-6| Custom Data Pointer, |5-0| Unused for Steinhart-Hart coefficients => equals 0
+                ThermalProbeType::Thermistor_Custom_SteinhartHart(_, _) => pointer << 6,
+                // |11-6| Custom Data Pointer, |5-0| Table Length - 1
+                _ => (pointer << 6) | (size / CUSTOM_DATA_BLOCK_SIZE - 1) as u64
             }
         }
+        None => 0
+    };
+
+    let word = match probe {
+        ThermalProbeType::Thermocouple_J(param) |
+        ThermalProbeType::Thermocouple_K(param) |
+        ThermalProbeType::Thermocouple_E(param) |
+        ThermalProbeType::Thermocouple_N(param) |
+        ThermalProbeType::Thermocouple_R(param) |
+        ThermalProbeType::Thermocouple_S(param) |
+        ThermalProbeType::Thermocouple_T(param) |
+        ThermalProbeType::Thermocouple_B(param) |
+        ThermalProbeType::Thermocouple_Custom(param, _) => {
+            let mut word = BitWriter::default();
+            // The 32 bit data to be written to the channel configuration register has the following format for thermocouples
+            // |31-27| Thermocouple Type
+            word.write_bits(probe.identifier(), 5);
+            // |26-22| Could Junction Channel ID -> if no cold junction compensation is used this value will be 0
+            word.write_bits(match &param.cold_junction_channel { None => 0, Some(chan) => chan.identifier() }, 5);
+            // |21-18| Sensor Configuration
+            word.write_bits(param.config_to_bits(), 4);
+            // |17-12| Unused => equals 0
+            word.write_bits(0, 6);
+            // |11-0| Custom Thermocouple Data Pointer => only used by custom thermocouples
+            word.write_bits(custom_data_pointer, 12);
+
+            word.finish()
+        }
+        ThermalProbeType::RTD_PT10(param)   |
+        ThermalProbeType::RTD_PT50(param)   |
+        ThermalProbeType::RTD_PT100(param)  |
+        ThermalProbeType::RTD_PT200(param)  |
+        ThermalProbeType::RTD_PT500(param)  |
+        ThermalProbeType::RTD_PT1000(param) |
+        ThermalProbeType::RTD_1000(param)   |
+        ThermalProbeType::RTD_NI120(param)  |
+        ThermalProbeType::RTD_Custom(param, _) => {
+            let mut word = BitWriter::default();
+            // The 32 bit data to be written to the channel configuration register has the following format for thermocouples
+            // |31-27| RTD Type
+            word.write_bits(probe.identifier(), 5);
+            // |26-22| Rsense Channel Assignment
+            word.write_bits(param.r_sense_channel.identifier(), 5);
+            // |21-18| Sensor Configuration
+            word.write_bits(param.sensor_configuration.to_bits(), 4);
+            // |17-14| Excitation Current
+            word.write_bits(param.excitation_current.identifier(), 4);
+            // |13-12| Curve
+            word.write_bits(param.curve.identifier(), 2);
+            // |11-0| Custom RTD Data Pointer => only used by custom RTDs
+            word.write_bits(custom_data_pointer, 12);
+
+            word.finish()
+        }
+        ThermalProbeType::Thermistor_44004_44033(param) |
+        ThermalProbeType::Thermistor_44005_44030(param) |
+        ThermalProbeType::Thermistor_44007_44034(param) |
+        ThermalProbeType::Thermistor_44006_44031(param) |
+        ThermalProbeType::Thermistor_44008_44032(param) |
+        ThermalProbeType::Thermistor_YSI400(param)      |
+        ThermalProbeType::Thermistor_Spectrum(param)    |
+        ThermalProbeType::Thermistor_Custom_SteinhartHart(param, _) |
+        ThermalProbeType::Thermistor_Custom_Table(param, _) => {
+            let mut word = BitWriter::default();
+            // The 32 bit data to be written to the channel configuration register has the following format for thermistors
+            // |31-27| Thermistor Type
+            word.write_bits(probe.identifier(), 5);
+            // |26-22| Rsense Channel Assignment
+            word.write_bits(param.r_sense_channel.identifier(), 5);
+            // |21-19| Sensor Configuration
+            word.write_bits(param.config_to_bits(), 3);
+            // |18-15| Excitation Current
+            word.write_bits(param.excitation_current.identifier(), 4);
+            // |14-12| Unused => equals 0
+            word.write_bits(0, 3);
+            // |11-0| Custom Thermistor Data Pointer => only used by custom thermistors
+            word.write_bits(custom_data_pointer, 12);
+
+            word.finish()
+        }
+        ThermalProbeType::Diode(param) => {
+            let mut word = BitWriter::default();
+            word.write_bits(probe.identifier(), 5);
+            word.write_bits(param.to_bits(), 27);
+
+            word.finish()
+        }
+        ThermalProbeType::SenseResistor(resistance) => {
+            let mut word = BitWriter::default();
+            // The 32 bit data to be written to the channel configuration register has the following format for sense resistors
+            // |31-27| Thermocouple Type
+            word.write_bits(probe.identifier(), 5);
+            // |26-0| Fixed Point Floating point (17,10) no sign bit representing the resistance
+            let resistance_fixed_point = FixedU32::<U10>::from_num(*resistance);
+            word.write_bits(resistance_fixed_point.to_bits().into(), 27);
+
+            word.finish()
+        }
+        ThermalProbeType::DirectADC(config) => {
+            let mut word = BitWriter::default();
+            // The 32 bit data to be written to the channel configuration register has the following format for direct adc
+            // |31-27| Direct ADC Type
+            word.write_bits(probe.identifier(), 5);
+            // |26| Single Ended
+            word.write_bits(config.identifier(), 1);
+            // |25-0| Unused => equals 0
+            word.write_bits(0, 26);
+
+            word.finish()
+        }
+        ThermalProbeType::DirectADC_Custom(param, _) => {
+            let mut word = BitWriter::default();
+            // |31-27| Direct ADC Type
+            word.write_bits(probe.identifier(), 5);
+            // |26| Single Ended
+            word.write_bits(param.sensor_configuration.identifier(), 1);
+            // |25-13| Unused => equals 0
+            word.write_bits(0, 13);
+            // |12| Custom Table Enabled
+            word.write_bits(1, 1);
+            // |11-0| Custom Table Data Pointer
+            word.write_bits(custom_data_pointer, 12);
+
+            word.finish()
+        }
+    };
+    Ok(word)
+}
+
+/// assembles a 32 bit channel assignment word from its fields, starting with the most significant one
+#[derive(Default)]
+struct BitWriter {
+    word: u32
+}
+
+impl BitWriter {
+    fn write_bits(&mut self, value: u64, bits: u32) {
+        self.word = (self.word << bits) | (value as u32 & ((1 << bits) - 1));
+    }
+
+    fn finish(self) -> u32 {
+        self.word
+    }
+}
 
-        values.into_iter().reduce(|acc, e| {
-            acc.iter().zip(e.iter()).map(|(&a, &b)| a+b).collect::<Vec<f32>>() // do a component wise add of the values
-        }).and_then(|v| {
-            Some(v.iter().map(|x| x/(rounds as f32)).collect()) // calculate average by dividing by the amount of values captured
-        }).ok_or(LTC2983Error::AvgCalculationError)
+/// calculate the 6 bit custom data pointer for data of `size` bytes placed at `address`
+///
+/// returns `None` if the data is not aligned to a table entry or does not fit the custom data ram
+fn custom_data_pointer(address: u16, size: u16) -> Option<u64> {
+    if address < CUSTOM_DATA_START_ADDRESS
+        || !(address - CUSTOM_DATA_START_ADDRESS).is_multiple_of(CUSTOM_DATA_BLOCK_SIZE)
+        || address as u32 + size as u32 > CUSTOM_DATA_END_ADDRESS as u32 + 1 {
+        None
+    } else {
+        Some(((address - CUSTOM_DATA_START_ADDRESS) / CUSTOM_DATA_BLOCK_SIZE).into())
     }
 }
 
+/// convert a value into the 24 bit unsigned fixed point format used by the custom tables
+fn to_fixed_u24<Frac: LeEqU32>(value: f32) -> Option<u32> {
+    FixedU32::<Frac>::checked_from_num(value).map(|v| v.to_bits()).filter(|bits| *bits <= 0xffffff)
+}
+
+/// convert a value into the 24 bit signed fixed point format used by the custom tables, the
+/// result holds the two's complement representation in the lower 24 bits
+fn to_fixed_i24<Frac: LeEqU32>(value: f32) -> Option<u32> {
+    FixedI32::<Frac>::checked_from_num(value).map(|v| v.to_bits()).filter(|bits| (-0x800000..=0x7fffff).contains(bits)).map(|bits| bits as u32 & 0xffffff)
+}
+
+/// convert a 24 bit unsigned fixed point value back into a float
+fn from_fixed_u24<Frac: LeEqU32>(bits: u32) -> f32 {
+    FixedU32::<Frac>::from_bits(bits & 0xffffff).to_num()
+}
+
+/// convert a 24 bit signed fixed point value back into a float
+fn from_fixed_i24<Frac: LeEqU32>(bits: u32) -> f32 {
+    FixedI32::<Frac>::from_bits(((bits << 8) as i32) >> 8).to_num()
+}
+
+/// location and size of the custom data referenced by a channel assignment word
+fn custom_data_location(word: u32) -> Option<(u16, u16)> {
+    let address = CUSTOM_DATA_START_ADDRESS + ((word >> 6) & 0x3f) as u16 * CUSTOM_DATA_BLOCK_SIZE;
+    let size = match word >> 27 {
+        9 | 18 | 27 => ((word & 0x3f) as u16 + 1) * CUSTOM_DATA_BLOCK_SIZE,
+        26 => SteinhartHartCoefficients::SIZE,
+        // direct adc channels only reference a table when the custom table bit is set
+        30 if word & (1 << 12) != 0 => ((word & 0x3f) as u16 + 1) * CUSTOM_DATA_BLOCK_SIZE,
+        _ => return None
+    };
+    custom_data_pointer(address, size).map(|_| (address, size))
+}
+
+/// instruction byte and 16 bit address every SPI transaction starts with
+fn frame_header(instruction: u8, address: u16) -> [u8; 3] {
+    let [high, low] = address.to_be_bytes();
+    [instruction, high, low]
+}
+
 fn reformat_fixedf24_to_fixed_f32(bytes_f24: &[u8; 3]) -> [u8; 4]{
     if bytes_f24[0] & 0x80 == 0x80 {
         [0xff, bytes_f24[0], bytes_f24[1], bytes_f24[2]]
@@ -915,7 +2693,43 @@ mod tests {
 
     use super::*;
 
+    //device whose memory never changes, reads are answered from it and writes are ignored
+    struct FixedMemory([u8; 0x400]);
+
+    impl embedded_hal::spi::ErrorType for FixedMemory {
+        type Error = core::convert::Infallible;
+    }
+
+    impl SpiDevice for FixedMemory {
+        fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+            let mut address = None;
+            for operation in operations {
+                match operation {
+                    // the first write carries the instruction and address
+                    Operation::Write(header) if address.is_none() => address = Some(u16::from_be_bytes([header[1], header[2]]) as usize),
+                    Operation::Read(words) => {
+                        let start = address.unwrap_or(0);
+                        words.copy_from_slice(&self.0[start..start + words.len()]);
+                    }
+                    _ => {}
+                }
+            }
+            Ok(())
+        }
+    }
+
+    //delay that only counts the time it was asked to wait
+    #[derive(Default)]
+    struct CountingDelay(u64);
+
+    impl DelayNs for CountingDelay {
+        fn delay_ns(&mut self, ns: u32) {
+            self.0 += ns as u64;
+        }
+    }
+
     #[test]
+    #[allow(clippy::unnecessary_cast)]
     fn test_fixedf24_u10_to_f32_signed() {
         let bytes: [u8; 3] = [ 0x7f, 0xff, 0xff ];
         let value = FixedI32::<U10>::from_be_bytes(reformat_fixedf24_to_fixed_f32(&bytes));
@@ -953,4 +2767,330 @@ mod tests {
         let value = FixedI32::<U10>::from_be_bytes(reformat_fixedf24_to_fixed_f32(&bytes));
         assert!(value.to_num::<f32>() - (-459.67 as f32) < 1./1027.); // error should be smaller than smallest fixed point value 1./1024.
     }
+
+    #[test]
+    fn test_thermistor_config_bits() {
+        let param = ThermistorParameters::default();
+        assert_eq!(param.config_to_bits(), 0b100);
+
+        // excitation sharing is not available for single ended thermistors
+        let param = ThermistorParameters::default().excitation_mode(ThermistorExcitationMode::SharingRotation);
+        assert_eq!(param.config_to_bits(), 0b100);
+
+        let param = ThermistorParameters::default()
+            .sensor_configuration(SensorConfiguration::Differential)
+            .excitation_mode(ThermistorExcitationMode::SharingNoRotation);
+        assert_eq!(param.config_to_bits(), 0b010);
+    }
+
+    #[test]
+    fn test_custom_data_pointer() {
+        assert_eq!(custom_data_pointer(0x250, SteinhartHartCoefficients::SIZE), Some(0));
+        assert_eq!(custom_data_pointer(0x256, SteinhartHartCoefficients::SIZE), Some(1));
+        assert_eq!(custom_data_pointer(0x3B8, SteinhartHartCoefficients::SIZE), Some(60));
+        assert_eq!(custom_data_pointer(0x3BE, SteinhartHartCoefficients::SIZE), None); // runs past the end of the custom data ram
+        assert_eq!(custom_data_pointer(0x251, SteinhartHartCoefficients::SIZE), None); // not aligned to a table entry
+        assert_eq!(custom_data_pointer(0x24C, SteinhartHartCoefficients::SIZE), None); // channel assignment memory
+    }
+
+    #[test]
+    fn test_steinhart_hart_coefficient_bytes() {
+        let coefficients = SteinhartHartCoefficients::new(1., -2., 0., 0., 0., 0.);
+        let bytes = coefficients.to_bytes();
+        assert_eq!(bytes[0..4], [0x3f, 0x80, 0x00, 0x00]);
+        assert_eq!(bytes[4..8], [0xc0, 0x00, 0x00, 0x00]);
+        assert_eq!(bytes[8..24], [0; 16]);
+    }
+
+    #[test]
+    fn test_custom_thermistor_table() {
+        assert_eq!(CustomThermistorTable::new(&[(100., 300.)]).unwrap_err(), CustomTableError::InvalidLength(1));
+        assert_eq!(CustomThermistorTable::new(&[(100., 300.), (100., 290.)]).unwrap_err(), CustomTableError::NotMonotonic(1));
+        assert_eq!(CustomThermistorTable::new(&[(100., 300.), (300000., 200.)]).unwrap_err(), CustomTableError::OutOfRange(1));
+        assert_eq!(CustomThermistorTable::new(&[(-1., 300.), (100., 200.)]).unwrap_err(), CustomTableError::OutOfRange(0));
+
+        let table = CustomThermistorTable::new(&[(1., 1.), (2.5, 0.5)]).unwrap();
+        assert_eq!(table.to_bytes(), [0x00, 0x00, 0x40, 0x00, 0x04, 0x00,
+                                          0x00, 0x00, 0xa0, 0x00, 0x02, 0x00]);
+    }
+
+    #[test]
+    fn test_custom_thermocouple_table() {
+        assert_eq!(CustomThermocoupleTable::new(&[(-1., 300.), (1., 290.)]).unwrap_err(), CustomTableError::NotMonotonic(1));
+        assert_eq!(CustomThermocoupleTable::new(&[(0., 300.); 65]).unwrap_err(), CustomTableError::InvalidLength(65));
+        assert_eq!(CustomThermocoupleTable::new(&[(-9000., 3.), (1., 290.)]).unwrap_err(), CustomTableError::OutOfRange(0));
+
+        let table = CustomThermocoupleTable::new(&[(-1., 1.), (2., 2.)]).unwrap();
+        assert_eq!(table.to_bytes(), [0xff, 0xfc, 0x00, 0x00, 0x04, 0x00,
+                                          0x00, 0x08, 0x00, 0x00, 0x08, 0x00]);
+    }
+
+    #[test]
+    fn test_custom_rtd_table() {
+        assert_eq!(CustomRTDTable::new(&[(100., 273.15), (20000., 300.)]).unwrap_err(), CustomTableError::OutOfRange(1));
+        assert_eq!(CustomRTDTable::new(&[(100., 273.15), (90., 300.)]).unwrap_err(), CustomTableError::NotMonotonic(1));
+
+        let table = CustomRTDTable::new(&[(100., 273.25), (138.5, 373.5)]).unwrap();
+        assert_eq!(table.to_bytes(), [0x01, 0x90, 0x00, 0x04, 0x45, 0x00,
+                                          0x02, 0x2a, 0x00, 0x05, 0xd6, 0x00]);
+    }
+
+    #[test]
+    fn test_voltage_result() {
+        match LTC2983Result::from_voltage_bytes([0x01, 0x20, 0x00, 0x00]) {
+            LTC2983Result::Valid(voltage) => assert_eq!(voltage, 1.),
+            res => panic!("unexpected result {res:?}")
+        }
+        match LTC2983Result::from_voltage_bytes([0x01, 0xf0, 0x00, 0x00]) {
+            LTC2983Result::Valid(voltage) => assert_eq!(voltage, -0.5),
+            res => panic!("unexpected result {res:?}")
+        }
+    }
+
+    #[test]
+    fn test_wake_timeout() {
+        // a missing device or a MISO line stuck low or high never reports ready
+        for level in [0x00, 0xFF] {
+            let mut delay = CountingDelay::default();
+            let mut ltc = LTC2983::new(FixedMemory([level; 0x400]));
+            assert!(matches!(ltc.wake(&mut delay), Err(LTC2983Error::DeviceNotReady)));
+            assert_eq!(delay.0, READY_TIMEOUT_US as u64 * 1000);
+        }
+    }
+
+    #[test]
+    fn test_eeprom_status() {
+        let device = |eeprom_status| {
+            let mut memory = [0; 0x400];
+            memory[STATUS_REGISTER as usize] = 0x40;
+            memory[EEPROM_STATUS_REGISTER as usize] = eeprom_status;
+            LTC2983::with_variant(FixedMemory(memory), LTC2984Variant)
+        };
+        let mut delay = CountingDelay::default();
+        assert!(device(0x00).save_to_eeprom(&mut delay).is_ok());
+        assert!(device(0x00).restore_from_eeprom(&mut delay).is_ok());
+        // a locked eeprom reports a non zero status
+        assert!(matches!(device(0x01).save_to_eeprom(&mut delay), Err(LTC2983Error::EepromError(0x01))));
+        assert!(matches!(device(0x01).restore_from_eeprom(&mut delay), Err(LTC2983Error::EepromError(0x01))));
+
+        // the eeprom commands give up on a device that never reports ready
+        for level in [0x00, 0xFF] {
+            let mut ltc = LTC2983::with_variant(FixedMemory([level; 0x400]), LTC2984Variant);
+            assert!(matches!(ltc.save_to_eeprom(&mut delay), Err(LTC2983Error::DeviceNotReady)));
+            assert!(matches!(ltc.restore_from_eeprom(&mut delay), Err(LTC2983Error::DeviceNotReady)));
+        }
+    }
+
+    #[test]
+    fn test_global_config_bits() {
+        assert_eq!(GlobalConfig::default().to_bits(), 0x00);
+        let config = GlobalConfig::default().temperature_unit(TemperatureUnit::Fahrenheit).filter_notch(FilterNotch::Reject50Hz);
+        assert_eq!(config.to_bits(), 0x06);
+        assert_eq!(GlobalConfig::from(config.to_bits()), config);
+        assert_eq!(GlobalConfig::from(0x01).notch(), FilterNotch::Reject60Hz);
+    }
+
+    #[test]
+    fn test_result_fault_classification() {
+        assert!(matches!(LTC2983Result::from([0x01, 0x00, 0x04, 0x00]), LTC2983Result::Valid(v) if v == 1.));
+        assert!(matches!(LTC2983Result::from([0x80, 0x00, 0x04, 0x00]), LTC2983Result::Invalid(f) if f == FaultFlags::SENSOR_HARD_FAULT));
+        assert!(matches!(LTC2983Result::from([0x21, 0x00, 0x04, 0x00]), LTC2983Result::Invalid(f) if f == FaultFlags::CJ_HARD_FAULT | FaultFlags::VALID));
+        assert!(matches!(LTC2983Result::from([0x09, 0x00, 0x04, 0x00]), LTC2983Result::Suspect(v, f) if v == 1. && f == FaultFlags::SENSOR_OVER_RANGE | FaultFlags::VALID));
+        assert!(matches!(LTC2983Result::from([0x02, 0x00, 0x04, 0x00]), LTC2983Result::Suspect(_, f) if f == FaultFlags::ADC_OUT_OF_RANGE));
+    }
+
+    #[test]
+    fn test_decode_channel_word() {
+        // type T thermocouple with CH2 as cold junction, single ended, 10µA open circuit current
+        let probe = ThermalProbeType::from_bits((7 << 27) | (2 << 22) | (0b1100 << 18), &[]).unwrap();
+        assert_eq!(probe, ThermalProbeType::Thermocouple_T(ThermocoupleParameters::default().cold_junction(LTC2983Channel::CH2)));
+
+        // 4 wire PT100 with rsense on CH3, current source rotation, 100µA, american curve
+        let word = (12 << 27) | (3 << 22) | (0b1010 << 18) | (5 << 14) | (1 << 12);
+        let probe = ThermalProbeType::from_bits(word, &[]).unwrap();
+        assert_eq!(probe, ThermalProbeType::RTD_PT100(RTDParameters::default()
+                                                      .channel(LTC2983Channel::CH3)
+                                                      .sensor_configuration(RTDSensorConfiguration::default().wire_cnt(RTDWireCount::Wire4).current_source_rotation(true))
+                                                      .excitation_current(RTDExcitationCurrent::I100uA)
+                                                      .curve(RTDCurve::American)));
+
+        // diode with ideality factor 1.0
+        let word = (28 << 27) | (1 << 26) | (1 << 24) | (1 << 22) | (1 << 20);
+        let probe = ThermalProbeType::from_bits(word, &[]).unwrap();
+        assert_eq!(probe, ThermalProbeType::Diode(DiodeParameters::default().ideality_factor(1.).excitation_current(DiodeExcitationCurrent::I20uA)));
+
+        // sense resistor with 2k ohm
+        let probe = ThermalProbeType::from_bits((29 << 27) | (2000 << 10), &[]).unwrap();
+        assert_eq!(probe, ThermalProbeType::SenseResistor(2000.));
+
+        // custom thermistor table with 2 entries at 0x256
+        let table = CustomThermistorTable::new(&[(1., 300.), (2.5, 200.)]).unwrap();
+        let word = (27 << 27) | (2 << 22) | (0b100 << 19) | (12 << 15) | (1 << 6) | 1;
+        let probe = ThermalProbeType::from_bits(word, &table.to_bytes()).unwrap();
+        assert_eq!(probe, ThermalProbeType::Thermistor_Custom_Table(ThermistorParameters::default().custom_address(0x256), table));
+
+        // unassigned channel and reserved sensor types
+        assert_eq!(ThermalProbeType::from_bits(0, &[]), None);
+        assert_eq!(ThermalProbeType::from_bits(31 << 27, &[]), None);
+    }
+
+    #[test]
+    fn test_validate_channel_plan() {
+        let mut plan: [Option<ThermalProbeType>; 20] = Default::default();
+        plan[1] = Some(ThermalProbeType::SenseResistor(2000.));
+        // the rtd shares CH2 with its sense resistor
+        plan[2] = Some(ThermalProbeType::RTD_PT100(RTDParameters::default().channel(LTC2983Channel::CH2)));
+        plan[4] = Some(ThermalProbeType::Thermocouple_K(ThermocoupleParameters::default().cold_junction(LTC2983Channel::CH3)));
+        plan[10] = Some(ThermalProbeType::SenseResistor(10000.));
+        plan[11] = Some(ThermalProbeType::Thermistor_44006_44031(ThermistorParameters::default()
+                                                                 .channel(LTC2983Channel::CH11)
+                                                                 .sensor_configuration(SensorConfiguration::Differential)));
+        // two rtds in series share CH17 and the sense resistor on CH15
+        plan[14] = Some(ThermalProbeType::SenseResistor(2000.));
+        plan[16] = Some(ThermalProbeType::RTD_PT100(RTDParameters::default().channel(LTC2983Channel::CH15)));
+        plan[17] = Some(ThermalProbeType::RTD_PT100(RTDParameters::default().channel(LTC2983Channel::CH15)));
+        assert!(validate_channel_plan(&plan).is_empty());
+
+        // a sensor that does not reference the sense resistor can not share its inputs
+        plan[3] = Some(ThermalProbeType::DirectADC(SensorConfiguration::Differential));
+        assert_eq!(validate_channel_plan(&plan), [
+            ValidationError::InputConflict { channel: LTC2983Channel::CH4, other: LTC2983Channel::CH3, input: LTC2983Channel::CH3 },
+        ]);
+        plan[3] = None;
+
+        // the cold junction has to be a temperature sensor
+        plan[4] = Some(ThermalProbeType::Thermocouple_K(ThermocoupleParameters::default().cold_junction(LTC2983Channel::CH2)));
+        // the rsense channel has to hold a sense resistor
+        plan[7] = Some(ThermalProbeType::RTD_PT1000(RTDParameters::default()
+                                                     .channel(LTC2983Channel::CH5)
+                                                     .sensor_configuration(RTDSensorConfiguration::default().wire_cnt(RTDWireCount::Wire3).current_source_rotation(true))));
+        // differential sensor overlapping the rtd on CH8
+        plan[8] = Some(ThermalProbeType::Diode(DiodeParameters::default().sensor_configuration(SensorConfiguration::Differential)));
+        // no CH0 for differential sensors on CH1, and CH1 is used by the sense resistor on CH2
+        plan[0] = Some(ThermalProbeType::DirectADC(SensorConfiguration::Differential));
+
+        assert_eq!(validate_channel_plan(&plan), [
+            ValidationError::NoNegativeInput(LTC2983Channel::CH1),
+            ValidationError::InputConflict { channel: LTC2983Channel::CH2, other: LTC2983Channel::CH1, input: LTC2983Channel::CH1 },
+            ValidationError::InvalidColdJunction { channel: LTC2983Channel::CH5, cold_junction_channel: LTC2983Channel::CH2 },
+            ValidationError::SenseResistorMissing { channel: LTC2983Channel::CH8, r_sense_channel: LTC2983Channel::CH5 },
+            ValidationError::RotationNotSupported { channel: LTC2983Channel::CH8, wire_cnt: RTDWireCount::Wire3 },
+            ValidationError::InputConflict { channel: LTC2983Channel::CH9, other: LTC2983Channel::CH8, input: LTC2983Channel::CH8 },
+        ]);
+    }
+
+    #[test]
+    fn test_channel_word_roundtrip() {
+        let probes = [
+            ThermalProbeType::Thermocouple_J(ThermocoupleParameters::default().cold_junction(LTC2983Channel::CH3).oc_current(LTC2983OcCurrent::I100uA)),
+            ThermalProbeType::RTD_PT1000(RTDParameters::default()
+                                         .channel(LTC2983Channel::CH4)
+                                         .sensor_configuration(RTDSensorConfiguration::default().wire_cnt(RTDWireCount::Wire4KelvinRsense).current_source_rotation(true))
+                                         .curve(RTDCurve::ITS_90)),
+            ThermalProbeType::Thermistor_YSI400(ThermistorParameters::default()
+                                                .sensor_configuration(SensorConfiguration::Differential)
+                                                .excitation_mode(ThermistorExcitationMode::SharingRotation)
+                                                .excitation_current(ThermistorExcitationCurrent::I10uA)),
+            ThermalProbeType::Thermistor_Custom_SteinhartHart(ThermistorParameters::default().custom_address(0x262),
+                                                              SteinhartHartCoefficients::new(1.1e-3, 2.4e-4, 0., 7.2e-8, 0., 0.)),
+            ThermalProbeType::RTD_Custom(RTDParameters::default().custom_address(0x250),
+                                         CustomRTDTable::new(&[(90., 250.), (100., 273.25), (110., 298.)]).unwrap()),
+            ThermalProbeType::Diode(DiodeParameters::default().use_avg(false).num_reading(DiodeReadingCount::READ3)),
+            ThermalProbeType::SenseResistor(1234.5),
+            ThermalProbeType::DirectADC(SensorConfiguration::Differential),
+            ThermalProbeType::DirectADC_Custom(DirectADCParameters::default().custom_address(0x2A4),
+                                               CustomADCTable::new(&[(-10., 100.), (0., -20.5), (500., 40.)]).unwrap()),
+        ];
+        for probe in probes {
+            let word = channel_word::<()>(&probe, &LTC2983Channel::CH5).unwrap();
+            let custom_data = probe.custom_data().map(|(_, data)| data).unwrap_or_default();
+            assert_eq!(ThermalProbeType::from_bits(word, &custom_data), Some(probe));
+        }
+
+        let probe = ThermalProbeType::Thermocouple_Custom(ThermocoupleParameters::default(), CustomThermocoupleTable::new(&[(0., 273.15), (1., 298.)]).unwrap());
+        assert!(matches!(channel_word::<()>(&probe, &LTC2983Channel::CH5), Err(LTC2983Error::CustomAddressMissing(LTC2983Channel::CH5))));
+    }
+
+    #[test]
+    fn test_device_variants() {
+        let diode = ThermalProbeType::Diode(DiodeParameters::default());
+        let custom_adc = ThermalProbeType::DirectADC_Custom(DirectADCParameters::default(), CustomADCTable::new(&[(0., 0.), (1., 1.)]).unwrap());
+        assert!(LTC2984Variant::supports(&diode));
+        assert!(!LTC2984Variant::supports(&custom_adc));
+        assert!(!LTC2986Variant::supports(&diode));
+        assert!(LTC2986_1Variant::supports(&custom_adc));
+        assert_eq!(LTC2986Variant::CHANNEL_COUNT, 10);
+
+        // channels referenced by a probe have to exist on the device as well
+        let mut ltc = LTC2983::with_variant(FixedMemory([0; 0x400]), LTC2986Variant);
+        let rtd = |r_sense| ThermalProbeType::RTD_PT100(RTDParameters::default().channel(r_sense));
+        assert!(ltc.setup_channel(rtd(LTC2983Channel::CH2), &LTC2983Channel::CH3).is_ok());
+        assert!(matches!(ltc.setup_channel(rtd(LTC2983Channel::CH11), &LTC2983Channel::CH3),
+                         Err(LTC2983Error::ChannelNotSupported(LTC2983Channel::CH11))));
+        let thermocouple = ThermalProbeType::Thermocouple_K(ThermocoupleParameters::default().cold_junction(LTC2983Channel::CH12));
+        assert!(matches!(ltc.setup_channel(thermocouple, &LTC2983Channel::CH1),
+                         Err(LTC2983Error::ChannelNotSupported(LTC2983Channel::CH12))));
+
+        // the voltage column of direct adc tables has to increase, the values may fall
+        assert_eq!(CustomADCTable::new(&[(1., 5.), (0., 6.)]), Err(CustomTableError::NotMonotonic(1)));
+        assert!(CustomADCTable::new(&[(0., 5.), (1., -6.)]).is_ok());
+    }
+
+    #[test]
+    fn test_config_serde() {
+        let config = LTC2983Config::default()
+            .global_config(GlobalConfig::default().filter_notch(FilterNotch::Reject50Hz))
+            .channel(LTC2983Channel::CH2, ThermalProbeType::SenseResistor(2000.))
+            .channel(LTC2983Channel::CH4, ThermalProbeType::RTD_PT100(RTDParameters::default().channel(LTC2983Channel::CH2)))
+            .channel(LTC2983Channel::CH6, ThermalProbeType::Thermocouple_Custom(ThermocoupleParameters::default().cold_junction(LTC2983Channel::CH4).custom_address(0x250),
+                                                                                CustomThermocoupleTable::new(&[(0., 273.25), (1., 298.5)]).unwrap()));
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(serde_json::from_str::<LTC2983Config>(&json).unwrap(), config);
+
+        // omitted parameters fall back to their defaults and only assigned channels need to be listed
+        let config: LTC2983Config = serde_json::from_str(r#"{
+            "channels": {
+                "CH3": { "Diode": { "ideality_factor": 1.0 } },
+                "CH5": { "Thermocouple_K": { "cold_junction_channel": "CH3" } }
+            }
+        }"#).unwrap();
+        assert_eq!(config, LTC2983Config::default()
+                   .channel(LTC2983Channel::CH3, ThermalProbeType::Diode(DiodeParameters::default().ideality_factor(1.)))
+                   .channel(LTC2983Channel::CH5, ThermalProbeType::Thermocouple_K(ThermocoupleParameters::default().cold_junction(LTC2983Channel::CH3))));
+
+        // custom tables are validated while deserializing
+        assert!(serde_json::from_str::<CustomRTDTable>("[[100.0, 273.15], [90.0, 300.0]]").is_err());
+    }
+
+    #[test]
+    fn test_custom_data_allocator() {
+        let mut allocator = CustomDataAllocator::new();
+        assert_eq!(allocator.allocate(SteinhartHartCoefficients::SIZE), Ok(0x250));
+        assert_eq!(allocator.allocate(10), Ok(0x268)); // rounded up to 2 blocks
+        assert_eq!(allocator.reserve(0x268, 6), Err(AllocationError::Unavailable(0x268, 6)));
+        assert_eq!(allocator.reserve(0x274, 6), Ok(()));
+        allocator.free(0x250, SteinhartHartCoefficients::SIZE);
+        assert_eq!(allocator.allocate(12), Ok(0x250));
+        assert_eq!(allocator.available(), 384 - 6 * 5);
+        assert_eq!(allocator.allocate(384), Err(AllocationError::Exhausted(384)));
+        allocator.clear();
+        assert_eq!(allocator.allocate(384), Ok(0x250));
+        assert_eq!(allocator.allocate(1), Err(AllocationError::Exhausted(1)));
+
+        // requests larger than the custom data ram never fit
+        allocator.clear();
+        assert_eq!(allocator.allocate(385), Err(AllocationError::Exhausted(385)));
+        assert_eq!(allocator.allocate(400), Err(AllocationError::Exhausted(400)));
+        assert_eq!(allocator.available(), 384);
+
+        let table = CustomRTDTable::new(&[(90., 250.), (100., 273.25), (110., 298.)]).unwrap();
+        let mut config = LTC2983Config::default()
+            .channel(LTC2983Channel::CH2, ThermalProbeType::SenseResistor(2000.))
+            .channel(LTC2983Channel::CH4, ThermalProbeType::RTD_Custom(RTDParameters::default().custom_address(0x2F8), table.clone()))
+            .channel(LTC2983Channel::CH6, ThermalProbeType::RTD_Custom(RTDParameters::default().custom_address(0x2F8), table.clone()));
+        assert_eq!(config.validate(), [ValidationError::CustomDataConflict(LTC2983Channel::CH6)]);
+        config.allocate_custom_data().unwrap();
+        assert!(config.validate().is_empty());
+        assert_eq!(config.probe(&LTC2983Channel::CH6), Some(&ThermalProbeType::RTD_Custom(RTDParameters::default().custom_address(0x262), table)));
+    }
 }
@@ -0,0 +1,1252 @@
+//! Software simulation of the LTC2983
+//!
+//! [`SimulatedLTC2983`] implements the blocking and the async `SpiDevice` traits, so the drivers of
+//! this crate can be run against it without hardware. It decodes the READ/WRITE framing of the
+//! device, keeps the complete register and RAM map and executes the commands written to the status
+//! register.
+//!
+//! Time passes on a [`VirtualClock`] instead of the wall clock. Every SPI transaction advances it by
+//! the time the bytes take on the bus and handing a clone of the clock to the drivers as their delay
+//! lets delays advance it as well. A conversion only completes once the clock has passed its
+//! duration, which depends on the sensor types of the converted channels and the mux configuration
+//! delay, so timeouts and polling can be tested deterministically.
+//!
+//! Every channel has the temperature of the sensor connected to it. A conversion computes the
+//! quantity the programmed sensor type produces at that temperature (thermocouple voltage
+//! including the cold junction, RTD and thermistor resistance, diode forward voltages) with
+//! optional [`SensorNoise`] and linearizes it again the way the device does, so the reported
+//! temperatures carry the noise and the cold junction and range errors a real setup would show.
+//!
+//! Faults can be injected into single channels with [`SimulatedLTC2983::inject_fault`], the device
+//! then reports the fault flags of the real chip in the result registers, stops signalling the end of
+//! conversions or fails SPI transactions.
+//!
+//!# Example
+//!``` rust
+//!    use ltc2983::{sim::SimulatedLTC2983, LTC2983, LTC2983Channel, LTC2983Result, ThermalProbeType, DiodeParameters};
+//!
+//!    let mut sim = SimulatedLTC2983::new();
+//!    sim.set_temperature(LTC2983Channel::CH2, 21.5);
+//!    let mut clock = sim.clock();
+//!
+//!    let mut ltc = LTC2983::new(sim);
+//!    ltc.setup_channel(ThermalProbeType::Diode(DiodeParameters::default()), &LTC2983Channel::CH2).unwrap();
+//!    ltc.start_conversion(&LTC2983Channel::CH2).unwrap();
+//!    ltc.wait_done(&mut clock, 500_000).unwrap();
+//!    assert_eq!(ltc.read_temperature(&LTC2983Channel::CH2).unwrap().result(), LTC2983Result::Valid(21.5));
+//!    // a diode with two readings takes two conversion cycles of 82ms
+//!    assert!(clock.now_us() >= 164_000);
+//!```
+
+use std::sync::{atomic::{AtomicU64, Ordering}, Arc};
+
+use embedded_hal::spi::{ErrorKind, ErrorType, Operation, SpiDevice};
+use fixed::types::extra::{U10, U21};
+
+use sensors::{invert, interpolate, Rtd, Thermistor, Thermocouple, ZERO_CELSIUS};
+
+mod sensors;
+
+use crate::{
+    custom_data_location, to_fixed_i24, DiodeExcitationCurrent, DiodeReadingCount, FaultFlags, GlobalConfig,
+    LTC2983Channel, RTDWireCount, TemperatureUnit, ThermalProbeType, ThermistorExcitationMode, ThermocoupleParameters,
+    EEPROM_KEY, EEPROM_KEY_REGISTER, EEPROM_READ_COMMAND, EEPROM_STATUS_REGISTER, EEPROM_WRITE_COMMAND,
+    GLOBAL_CONFIG_REGISTER, LTC2983_READ, LTC2983_WRITE, MULTI_CHANNEL_MASK_REGISTER, MUX_CONFIG_DELAY_REGISTER,
+    MUX_CONFIG_DELAY_STEP_US, SLEEP_COMMAND, START_CONVERSION_COMMAND, STATUS_REGISTER,
+};
+
+/// size of the simulated memory, covers all registers and the custom data ram
+pub const MEMORY_SIZE: usize = 0x400;
+const CHANNEL_COUNT: usize = 20;
+// the configuration the eeprom holds, global configuration up to the end of the custom data ram
+const EEPROM_START_ADDRESS: usize = GLOBAL_CONFIG_REGISTER as usize;
+const EEPROM_END_ADDRESS: usize = crate::CUSTOM_DATA_END_ADDRESS as usize;
+// status register value of an idle device, done bit set
+const STATUS_DONE: u8 = 0x40;
+// eeprom status reported when the command was not unlocked with the key
+const EEPROM_STATUS_LOCKED: u8 = 0x01;
+// ideality factor the device uses for diodes configured without one
+const DEFAULT_IDEALITY_FACTOR: f64 = 1.003;
+const DIODE_RANGE: (f64, f64) = (-60., 200.);
+const DEFAULT_SEED: u64 = 0x2983_2983_2983_2983;
+// temperature the device reports along with hard faults
+const HARD_FAULT_RESULT: f64 = -999.;
+// one conversion cycle with the default 50/60Hz rejection, most sensors take two cycles
+const CONVERSION_CYCLE_US: u64 = 82_000;
+// a byte takes 4µs on the bus at the maximum SCK frequency of 2MHz
+const SPI_BYTE_TIME_NS: u64 = 4_000;
+
+/// virtual time of a simulated device in ns, delays advance it instead of blocking
+///
+/// clones share the same time, so a clone can be handed to the drivers as their delay
+#[derive(Debug, Clone, Default)]
+pub struct VirtualClock {
+    now_ns: Arc<AtomicU64>
+}
+
+impl VirtualClock {
+    pub fn now_us(&self) -> u64 {
+        self.now_ns() / 1000
+    }
+
+    pub fn advance_us(&self, us: u64) {
+        self.advance_ns(us * 1000);
+    }
+
+    fn now_ns(&self) -> u64 {
+        self.now_ns.load(Ordering::Relaxed)
+    }
+
+    fn advance_ns(&self, ns: u64) {
+        self.now_ns.fetch_add(ns, Ordering::Relaxed);
+    }
+}
+
+impl embedded_hal::delay::DelayNs for VirtualClock {
+    fn delay_ns(&mut self, ns: u32) {
+        self.advance_ns(ns.into());
+    }
+}
+
+impl embedded_hal_async::delay::DelayNs for VirtualClock {
+    async fn delay_ns(&mut self, ns: u32) {
+        self.advance_ns(ns.into());
+    }
+}
+
+//a conversion that completes once the clock reaches `done_ns`
+struct PendingConversion {
+    selection: u8,
+    channels: Vec<LTC2983Channel>,
+    done_ns: u64
+}
+
+/// fault the simulator injects into a channel
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Fault {
+    /// the sensor is disconnected, results carry a sensor hard fault
+    OpenSensor,
+    /// the sensor leads are shorted, results carry a sensor hard fault and under range, shorted
+    /// thermocouples report the temperature of their cold junction without any fault
+    ShortedSensor,
+    /// the input exceeds the range of the adc, results are flagged but still carry the reading
+    AdcOutOfRange,
+    /// the cold junction measurement failed, results carry a cold junction hard fault
+    ColdJunctionHardFault,
+    /// conversions including the channel never finish, the done bit stays cleared until the next command
+    StuckDoneBit,
+    /// SPI transactions accessing the configuration or result of the channel fail
+    SpiError
+}
+
+struct InjectedFault {
+    channel: LTC2983Channel,
+    fault: Fault,
+    // number of conversions or transactions left, `None` until cleared
+    remaining: Option<u32>
+}
+
+/// standard deviation of the noise added to the quantities the simulated device measures
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct SensorNoise {
+    thermocouple_uv: f32,
+    rtd_ohm: f32,
+    thermistor_ohm: f32,
+    diode_uv: f32,
+    adc_uv: f32
+}
+
+impl SensorNoise {
+    /// noise of thermocouple voltages in µV
+    pub fn thermocouple(mut self, uv: f32) -> Self { self.thermocouple_uv = uv; self }
+    /// noise of RTD resistances in Ω
+    pub fn rtd(mut self, ohm: f32) -> Self { self.rtd_ohm = ohm; self }
+    /// noise of thermistor resistances in Ω
+    pub fn thermistor(mut self, ohm: f32) -> Self { self.thermistor_ohm = ohm; self }
+    /// noise of every diode forward voltage reading in µV
+    pub fn diode(mut self, uv: f32) -> Self { self.diode_uv = uv; self }
+    /// noise of direct adc voltages in µV
+    pub fn adc(mut self, uv: f32) -> Self { self.adc_uv = uv; self }
+}
+
+//what the device reports for a channel
+enum Reading {
+    Temperature(f64),
+    Voltage(f64),
+    // the output of a custom direct adc table, reported without unit conversion
+    Value(f64)
+}
+
+/// simulated LTC2983 connected via SPI
+///
+/// every channel has the temperature in °C of the sensor connected to it and the voltage in V a
+/// direct adc measurement of the channel sees
+pub struct SimulatedLTC2983 {
+    memory: [u8; MEMORY_SIZE],
+    eeprom: [u8; EEPROM_END_ADDRESS - EEPROM_START_ADDRESS + 1],
+    temperatures: [f32; CHANNEL_COUNT],
+    voltages: [f32; CHANNEL_COUNT],
+    noise: SensorNoise,
+    rng: u64,
+    faults: Vec<InjectedFault>,
+    clock: VirtualClock,
+    conversion: Option<PendingConversion>,
+    sleeping: bool
+}
+
+impl Default for SimulatedLTC2983 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimulatedLTC2983 {
+    /// a freshly powered up device, all channels are unassigned and at 25°C
+    pub fn new() -> Self {
+        let mut memory = [0; MEMORY_SIZE];
+        memory[STATUS_REGISTER as usize] = STATUS_DONE;
+        SimulatedLTC2983 {
+            memory,
+            eeprom: [0; EEPROM_END_ADDRESS - EEPROM_START_ADDRESS + 1],
+            temperatures: [25.; CHANNEL_COUNT],
+            voltages: [0.; CHANNEL_COUNT],
+            noise: SensorNoise::default(),
+            rng: DEFAULT_SEED,
+            faults: Vec::new(),
+            clock: VirtualClock::default(),
+            conversion: None,
+            sleeping: false
+        }
+    }
+
+    /// temperature in °C of the sensor connected to `channel`
+    pub fn set_temperature(&mut self, channel: LTC2983Channel, celsius: f32) {
+        self.temperatures[channel.identifier() as usize - 1] = celsius;
+    }
+
+    /// voltage in V a direct adc measurement of `channel` reports
+    pub fn set_voltage(&mut self, channel: LTC2983Channel, volts: f32) {
+        self.voltages[channel.identifier() as usize - 1] = volts;
+    }
+
+    /// noise added to every measurement, no noise is added by default
+    pub fn set_noise(&mut self, noise: SensorNoise) {
+        self.noise = noise;
+    }
+
+    /// seed of the pseudo random noise, equal seeds reproduce equal results
+    pub fn set_seed(&mut self, seed: u64) {
+        // the generator state must never be zero
+        self.rng = if seed == 0 { DEFAULT_SEED } else { seed };
+    }
+
+    /// handle to the virtual time of the device
+    pub fn clock(&self) -> VirtualClock {
+        self.clock.clone()
+    }
+
+    /// inject `fault` into every conversion of `channel` until the faults of the channel are cleared
+    pub fn inject_fault(&mut self, channel: LTC2983Channel, fault: Fault) {
+        self.faults.push(InjectedFault { channel, fault, remaining: None });
+    }
+
+    /// inject `fault` into the next `count` conversions of `channel`, SPI errors fail the next `count`
+    /// transactions accessing the channel
+    pub fn inject_faults(&mut self, channel: LTC2983Channel, fault: Fault, count: u32) {
+        if count > 0 {
+            self.faults.push(InjectedFault { channel, fault, remaining: Some(count) });
+        }
+    }
+
+    /// remove all faults injected into `channel`
+    pub fn clear_faults(&mut self, channel: LTC2983Channel) {
+        self.faults.retain(|injected| injected.channel != channel);
+    }
+
+    fn has_fault(&self, channel: &LTC2983Channel, fault: Fault) -> bool {
+        self.faults.iter().any(|injected| injected.channel == *channel && injected.fault == fault)
+    }
+
+    //count down the injected faults of a channel matching `affected`
+    fn consume_faults(&mut self, channel: &LTC2983Channel, affected: impl Fn(Fault) -> bool) {
+        for injected in self.faults.iter_mut().filter(|injected| injected.channel == *channel && affected(injected.fault)) {
+            if let Some(remaining) = &mut injected.remaining {
+                *remaining -= 1;
+            }
+        }
+        self.faults.retain(|injected| injected.remaining != Some(0));
+    }
+
+    //flags of the faults injected into a channel on top of the faults of its measurement
+    fn injected_faults(&self, channel: &LTC2983Channel, probe: &ThermalProbeType, mut faults: FaultFlags) -> FaultFlags {
+        if self.has_fault(channel, Fault::OpenSensor) {
+            faults |= FaultFlags::SENSOR_HARD_FAULT;
+        }
+        if self.has_fault(channel, Fault::ShortedSensor) && thermocouple_cold_junction(probe).is_none() {
+            faults |= FaultFlags::SENSOR_HARD_FAULT | FaultFlags::SENSOR_UNDER_RANGE;
+        }
+        if self.has_fault(channel, Fault::AdcOutOfRange) {
+            faults |= FaultFlags::ADC_OUT_OF_RANGE;
+        }
+        if self.has_fault(channel, Fault::ColdJunctionHardFault) {
+            faults |= FaultFlags::CJ_HARD_FAULT;
+        }
+        faults
+    }
+
+    //an injected SPI error fails frames accessing the configuration or result of its channel
+    fn spi_fault(&mut self, operations: &[Operation<'_, u8>]) -> bool {
+        let mut header = [0; 3];
+        let mut length = 0;
+        for operation in operations {
+            let (mosi, size): (&[u8], usize) = match operation {
+                Operation::Read(words) => (&[], words.len()),
+                Operation::Write(words) => (words, words.len()),
+                Operation::Transfer(read, write) => (write, read.len().max(write.len())),
+                Operation::TransferInPlace(words) => (words, words.len()),
+                Operation::DelayNs(_) => (&[], 0)
+            };
+            for i in 0..size {
+                if let Some(byte) = header.get_mut(length + i) {
+                    *byte = mosi.get(i).copied().unwrap_or(0);
+                }
+            }
+            length += size;
+        }
+        let start = u16::from_be_bytes([header[1], header[2]]) as usize;
+        let accessed = start..start + length.saturating_sub(header.len());
+        let overlaps = |address: u16| accessed.start < address as usize + 4 && (address as usize) < accessed.end;
+        let failing = self.faults.iter()
+            .find(|injected| injected.fault == Fault::SpiError
+                && (overlaps(injected.channel.start_address()) || overlaps(injected.channel.result_address())))
+            .map(|injected| injected.channel);
+        match failing {
+            Some(channel) => {
+                self.consume_faults(&channel, |fault| fault == Fault::SpiError);
+                true
+            }
+            None => false
+        }
+    }
+
+    /// the complete register and RAM map of the device
+    pub fn memory(&self) -> &[u8] {
+        &self.memory
+    }
+
+    pub fn is_sleeping(&self) -> bool {
+        self.sleeping
+    }
+
+    fn read_u32(&self, address: u16) -> u32 {
+        let address = address as usize;
+        u32::from_be_bytes(self.memory[address..address + 4].try_into().unwrap())
+    }
+
+    fn write_u32(&mut self, address: u16, value: u32) {
+        let address = address as usize;
+        self.memory[address..address + 4].copy_from_slice(&value.to_be_bytes());
+    }
+
+    //decode the configuration currently assigned to a channel
+    fn channel_config(&self, channel: &LTC2983Channel) -> Option<ThermalProbeType> {
+        let word = self.read_u32(channel.start_address());
+        let custom_data = match custom_data_location(word) {
+            Some((address, size)) => &self.memory[address as usize..(address + size) as usize],
+            None => &[]
+        };
+        ThermalProbeType::from_bits(word, custom_data)
+    }
+
+    //execute a command written to the status register, a new command aborts a running conversion
+    fn execute(&mut self, command: u8) {
+        self.conversion = None;
+        match command {
+            SLEEP_COMMAND => self.sleeping = true,
+            EEPROM_WRITE_COMMAND | EEPROM_READ_COMMAND => {
+                // the key has to be written again before every eeprom command
+                let unlocked = self.read_u32(EEPROM_KEY_REGISTER) == EEPROM_KEY;
+                self.write_u32(EEPROM_KEY_REGISTER, 0);
+                if unlocked {
+                    if command == EEPROM_WRITE_COMMAND {
+                        self.eeprom.copy_from_slice(&self.memory[EEPROM_START_ADDRESS..=EEPROM_END_ADDRESS]);
+                    } else {
+                        self.memory[EEPROM_START_ADDRESS..=EEPROM_END_ADDRESS].copy_from_slice(&self.eeprom);
+                    }
+                }
+                self.memory[EEPROM_STATUS_REGISTER as usize] = if unlocked { 0 } else { EEPROM_STATUS_LOCKED };
+                self.memory[STATUS_REGISTER as usize] = STATUS_DONE;
+            }
+            _ if command & 0xe0 == START_CONVERSION_COMMAND => {
+                let selection = command & 0x1f;
+                let mask = if selection == 0 {
+                    // channel 0 converts all channels of the multi channel mask
+                    self.read_u32(MULTI_CHANNEL_MASK_REGISTER)
+                } else {
+                    LTC2983Channel::from_identifier(selection.into()).map_or(0, |channel| channel.mask())
+                };
+                let channels: Vec<LTC2983Channel> = (1..=CHANNEL_COUNT as u64)
+                    .filter_map(LTC2983Channel::from_identifier)
+                    .filter(|channel| mask & channel.mask() != 0)
+                    .collect();
+                if channels.iter().any(|channel| self.has_fault(channel, Fault::StuckDoneBit)) {
+                    for channel in &channels {
+                        self.consume_faults(channel, |fault| fault == Fault::StuckDoneBit);
+                    }
+                    self.memory[STATUS_REGISTER as usize] = START_CONVERSION_COMMAND | selection;
+                    return;
+                }
+                let mux_delay_us = self.memory[MUX_CONFIG_DELAY_REGISTER as usize] as u64 * MUX_CONFIG_DELAY_STEP_US as u64;
+                let duration_us: u64 = channels.iter()
+                    .map(|channel| match self.conversion_cycles(channel) {
+                        0 => 0,
+                        cycles => mux_delay_us + cycles * CONVERSION_CYCLE_US
+                    })
+                    .sum();
+                self.conversion = Some(PendingConversion { selection, channels, done_ns: self.clock.now_ns() + duration_us * 1000 });
+                self.memory[STATUS_REGISTER as usize] = START_CONVERSION_COMMAND | selection;
+            }
+            // unknown commands are ignored by the device
+            _ => self.memory[STATUS_REGISTER as usize] = STATUS_DONE
+        }
+    }
+
+    //number of conversion cycles the device needs for a channel, unassigned channels and sense resistors are skipped
+    fn conversion_cycles(&self, channel: &LTC2983Channel) -> u64 {
+        match self.channel_config(channel) {
+            None | Some(ThermalProbeType::SenseResistor(_)) => 0,
+            Some(probe) => match thermocouple_cold_junction(&probe) {
+                // the cold junction sensor is converted along with the thermocouple
+                Some(Some(cold_junction)) => self.channel_config(&cold_junction).map_or(2, |sensor| sensor_cycles(&sensor)),
+                _ => sensor_cycles(&probe)
+            }
+        }
+    }
+
+    //finish the running conversion once the clock has passed its duration
+    fn settle(&mut self) {
+        if self.conversion.as_ref().is_some_and(|conversion| self.clock.now_ns() >= conversion.done_ns) {
+            if let Some(conversion) = self.conversion.take() {
+                for channel in &conversion.channels {
+                    self.convert(channel);
+                }
+                self.memory[STATUS_REGISTER as usize] = STATUS_DONE | conversion.selection;
+            }
+        }
+    }
+
+    //convert a single channel and store its result, unassigned channels and sense resistors produce no result
+    fn convert(&mut self, channel: &LTC2983Channel) {
+        let measurement = self.channel_config(channel).and_then(|probe| self.measure(channel, &probe));
+        self.consume_faults(channel, |fault| fault != Fault::SpiError);
+        let Some((reading, faults)) = measurement else {
+            return;
+        };
+        let result = match reading {
+            _ if faults.is_hard_fault() => encode_result::<U10>(HARD_FAULT_RESULT, faults),
+            Reading::Temperature(celsius) => {
+                let temperature = match GlobalConfig::from(self.memory[GLOBAL_CONFIG_REGISTER as usize]).unit() {
+                    TemperatureUnit::Celsius => celsius,
+                    TemperatureUnit::Fahrenheit => celsius * 9. / 5. + 32.
+                };
+                encode_result::<U10>(temperature, faults)
+            }
+            Reading::Voltage(volts) => encode_result::<U21>(volts, faults),
+            Reading::Value(value) => encode_result::<U10>(value, faults)
+        };
+        self.write_u32(channel.result_address(), result);
+    }
+
+    //the reading of a channel together with the faults the device detects
+    fn measure(&mut self, channel: &LTC2983Channel, probe: &ThermalProbeType) -> Option<(Reading, FaultFlags)> {
+        let index = channel.identifier() as usize - 1;
+        let adc_noise = self.noise.adc_uv as f64 * 1e-6;
+        let (reading, faults) = match probe {
+            ThermalProbeType::SenseResistor(_) => return None,
+            ThermalProbeType::DirectADC(_) => (Reading::Voltage(self.voltages[index] as f64 + self.noise(adc_noise)), FaultFlags::empty()),
+            ThermalProbeType::DirectADC_Custom(_, table) => {
+                let millivolts = (self.voltages[index] as f64 + self.noise(adc_noise)) * 1e3;
+                let points = table_points(table.entries(), |voltage, value| (voltage, value));
+                let (low, high) = (points[0].0, points[points.len() - 1].0);
+                let faults = if millivolts > high {
+                    FaultFlags::SENSOR_OVER_RANGE
+                } else if millivolts < low {
+                    FaultFlags::SENSOR_UNDER_RANGE
+                } else {
+                    FaultFlags::empty()
+                };
+                (Reading::Value(interpolate(&points, millivolts)), faults)
+            }
+            _ => {
+                // a shorted thermocouple only sees the temperature of its cold junction
+                let celsius = match thermocouple_cold_junction(probe) {
+                    Some(cold_junction) if self.has_fault(channel, Fault::ShortedSensor) => {
+                        cold_junction.map_or(0., |cold_junction| self.temperatures[cold_junction.identifier() as usize - 1] as f64)
+                    }
+                    _ => self.temperatures[index] as f64
+                };
+                let (celsius, faults) = self.measure_temperature(probe, celsius);
+                (Reading::Temperature(celsius), faults)
+            }
+        };
+        Some((reading, self.injected_faults(channel, probe, faults)))
+    }
+
+    //temperature the device derives from a sensor at `celsius`
+    fn measure_temperature(&mut self, probe: &ThermalProbeType, celsius: f64) -> (f64, FaultFlags) {
+        let rtd_noise = self.noise.rtd_ohm as f64;
+        let thermistor_noise = self.noise.thermistor_ohm as f64;
+        // RTDs and thermistors are measured against their sense resistor, without one there is no excitation
+        if let Some(r_sense_channel) = probe.r_sense_channel() {
+            if !matches!(self.channel_config(&r_sense_channel), Some(ThermalProbeType::SenseResistor(_))) {
+                return (0., FaultFlags::SENSOR_HARD_FAULT);
+            }
+        }
+        match probe {
+            ThermalProbeType::Thermocouple_J(param) => self.measure_thermocouple(&sensors::THERMOCOUPLE_J, param, celsius),
+            ThermalProbeType::Thermocouple_K(param) => self.measure_thermocouple(&sensors::THERMOCOUPLE_K, param, celsius),
+            ThermalProbeType::Thermocouple_E(param) => self.measure_thermocouple(&sensors::THERMOCOUPLE_E, param, celsius),
+            ThermalProbeType::Thermocouple_N(param) => self.measure_thermocouple(&sensors::THERMOCOUPLE_N, param, celsius),
+            ThermalProbeType::Thermocouple_R(param) => self.measure_thermocouple(&sensors::THERMOCOUPLE_R, param, celsius),
+            ThermalProbeType::Thermocouple_S(param) => self.measure_thermocouple(&sensors::THERMOCOUPLE_S, param, celsius),
+            ThermalProbeType::Thermocouple_T(param) => self.measure_thermocouple(&sensors::THERMOCOUPLE_T, param, celsius),
+            ThermalProbeType::Thermocouple_B(param) => self.measure_thermocouple(&sensors::THERMOCOUPLE_B, param, celsius),
+            ThermalProbeType::Thermocouple_Custom(param, table) => {
+                let points = table_points(table.entries(), |voltage, kelvin| (kelvin - ZERO_CELSIUS, voltage));
+                let range = (points[0].0, points[points.len() - 1].0);
+                self.measure_cold_junction_compensated(|t| interpolate(&points, t), range, param, celsius)
+            }
+            ThermalProbeType::RTD_PT10(param)   => self.measure_rtd(&Rtd::platinum(10., &param.curve), celsius),
+            ThermalProbeType::RTD_PT50(param)   => self.measure_rtd(&Rtd::platinum(50., &param.curve), celsius),
+            ThermalProbeType::RTD_PT100(param)  => self.measure_rtd(&Rtd::platinum(100., &param.curve), celsius),
+            ThermalProbeType::RTD_PT200(param)  => self.measure_rtd(&Rtd::platinum(200., &param.curve), celsius),
+            ThermalProbeType::RTD_PT500(param)  => self.measure_rtd(&Rtd::platinum(500., &param.curve), celsius),
+            ThermalProbeType::RTD_PT1000(param) => self.measure_rtd(&Rtd::platinum(1000., &param.curve), celsius),
+            ThermalProbeType::RTD_1000(_)       => self.measure_rtd(&Rtd::platinum_375(), celsius),
+            ThermalProbeType::RTD_NI120(_)      => self.measure_rtd(&Rtd::nickel_120(), celsius),
+            ThermalProbeType::RTD_Custom(_, table) => {
+                let points = table_points(table.entries(), |ohm, kelvin| (kelvin - ZERO_CELSIUS, ohm));
+                let range = (points[0].0, points[points.len() - 1].0);
+                self.measure_resistance(|t| interpolate(&points, t), range, rtd_noise, celsius)
+            }
+            ThermalProbeType::Thermistor_44004_44033(_) => self.measure_thermistor(&Thermistor::YSI_44004, celsius),
+            ThermalProbeType::Thermistor_44005_44030(_) => self.measure_thermistor(&Thermistor::YSI_44005, celsius),
+            ThermalProbeType::Thermistor_44007_44034(_) => self.measure_thermistor(&Thermistor::YSI_44007, celsius),
+            ThermalProbeType::Thermistor_44006_44031(_) => self.measure_thermistor(&Thermistor::YSI_44006, celsius),
+            ThermalProbeType::Thermistor_44008_44032(_) => self.measure_thermistor(&Thermistor::YSI_44008, celsius),
+            ThermalProbeType::Thermistor_YSI400(_)      => self.measure_thermistor(&Thermistor::YSI_400, celsius),
+            ThermalProbeType::Thermistor_Spectrum(_)    => self.measure_thermistor(&Thermistor::SPECTRUM_1003K, celsius),
+            ThermalProbeType::Thermistor_Custom_SteinhartHart(_, coefficients) => {
+                let thermistor = Thermistor { coefficients: coefficients.coefficients.map(f64::from) };
+                self.measure_thermistor(&thermistor, celsius)
+            }
+            ThermalProbeType::Thermistor_Custom_Table(_, table) => {
+                // the resistance increases along the table while the temperature falls
+                let points = table_points(table.entries(), |ohm, kelvin| (kelvin - ZERO_CELSIUS, ohm));
+                let range = (points[points.len() - 1].0, points[0].0);
+                self.measure_resistance(|t| interpolate(&points, t), range, thermistor_noise, celsius)
+            }
+            ThermalProbeType::Diode(param) => {
+                let ideality = param.idealitiy_factor.map_or(DEFAULT_IDEALITY_FACTOR, f64::from);
+                let current = match param.excitation_current {
+                    DiodeExcitationCurrent::I10uA => 10e-6,
+                    DiodeExcitationCurrent::I20uA => 20e-6,
+                    DiodeExcitationCurrent::I40uA => 40e-6,
+                    DiodeExcitationCurrent::I80uA => 80e-6
+                };
+                let noise = self.noise.diode_uv as f64 * 1e-6;
+                let mut reading = |scale: f64| sensors::diode_voltage(ideality, current * scale, celsius) + self.noise(noise);
+                // the difference of the forward voltages at multiples of the excitation current is proportional to kT/q
+                let thermal_voltage = match param.num_reading {
+                    DiodeReadingCount::READ2 => (reading(8.) - reading(1.)) / 8f64.ln(),
+                    // the third reading cancels the voltage drop on the lead resistance
+                    DiodeReadingCount::READ3 => {
+                        let (v1, v4, v8) = (reading(1.), reading(4.), reading(8.));
+                        (4. * (v4 - v1) - 3. * (v8 - v4)) / (5. * 2f64.ln())
+                    }
+                };
+                invert(sensors::thermal_voltage, thermal_voltage / ideality, DIODE_RANGE)
+            }
+            ThermalProbeType::SenseResistor(_) | ThermalProbeType::DirectADC(_) | ThermalProbeType::DirectADC_Custom(_, _) => {
+                (0., FaultFlags::SENSOR_HARD_FAULT)
+            }
+        }
+    }
+
+    fn measure_thermocouple(&mut self, thermocouple: &Thermocouple, param: &ThermocoupleParameters, celsius: f64) -> (f64, FaultFlags) {
+        self.measure_cold_junction_compensated(|t| thermocouple.emf(t), thermocouple.range, param, celsius)
+    }
+
+    //the thermocouple voltage depends on the temperature difference to the cold junction, the device
+    //adds the voltage a thermocouple at the measured cold junction temperature would produce
+    fn measure_cold_junction_compensated(&mut self, emf: impl Fn(f64) -> f64, range: (f64, f64), param: &ThermocoupleParameters, celsius: f64) -> (f64, FaultFlags) {
+        // without a cold junction sensor the device assumes the cold junction at 0°C
+        let (cold_junction, (measured_cold_junction, mut faults)) = match &param.cold_junction_channel {
+            Some(channel) => (self.temperatures[channel.identifier() as usize - 1] as f64, self.measure_cold_junction(channel)),
+            None => (0., (0., FaultFlags::empty()))
+        };
+        if faults.is_hard_fault() {
+            return (0., faults);
+        }
+        let millivolts = emf(celsius) - emf(cold_junction) + self.noise(self.noise.thermocouple_uv as f64 * 1e-3);
+        let (temperature, range_faults) = invert(&emf, millivolts + emf(measured_cold_junction), range);
+        faults |= range_faults;
+        (temperature, faults)
+    }
+
+    //temperature of a cold junction sensor, faults of the sensor are reported as cold junction faults
+    fn measure_cold_junction(&mut self, channel: &LTC2983Channel) -> (f64, FaultFlags) {
+        let celsius = self.temperatures[channel.identifier() as usize - 1] as f64;
+        match self.channel_config(channel) {
+            // only diodes, RTDs and thermistors can measure the cold junction
+            Some(probe) if matches!(probe.identifier(), 10..=28) => {
+                let (temperature, faults) = self.measure_temperature(&probe, celsius);
+                let faults = self.injected_faults(channel, &probe, faults);
+                if faults.is_hard_fault() {
+                    (temperature, FaultFlags::CJ_HARD_FAULT)
+                } else if !faults.is_empty() {
+                    (temperature, FaultFlags::CJ_SOFT_FAULT)
+                } else {
+                    (temperature, FaultFlags::empty())
+                }
+            }
+            _ => (0., FaultFlags::CJ_HARD_FAULT)
+        }
+    }
+
+    fn measure_rtd(&mut self, rtd: &Rtd, celsius: f64) -> (f64, FaultFlags) {
+        self.measure_resistance(|t| rtd.resistance(t), rtd.range, self.noise.rtd_ohm as f64, celsius)
+    }
+
+    fn measure_thermistor(&mut self, thermistor: &Thermistor, celsius: f64) -> (f64, FaultFlags) {
+        self.measure_resistance(|t| thermistor.resistance(t), Thermistor::RANGE, self.noise.thermistor_ohm as f64, celsius)
+    }
+
+    fn measure_resistance(&mut self, resistance: impl Fn(f64) -> f64, range: (f64, f64), noise: f64, celsius: f64) -> (f64, FaultFlags) {
+        let ohm = resistance(celsius) + self.noise(noise);
+        invert(resistance, ohm, range)
+    }
+
+    //normal distributed noise with standard deviation `sigma`
+    fn noise(&mut self, sigma: f64) -> f64 {
+        if sigma == 0. {
+            return 0.;
+        }
+        // box-muller transform of two uniform samples
+        let (u1, u2) = (self.uniform(), self.uniform());
+        sigma * (-2. * u1.ln()).sqrt() * (2. * core::f64::consts::PI * u2).cos()
+    }
+
+    //uniform sample in (0, 1) from a xorshift64* generator
+    fn uniform(&mut self) -> f64 {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        let bits = self.rng.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11;
+        (bits as f64 + 0.5) / (1u64 << 53) as f64
+    }
+
+    //run the frame of a single chip select period through the device
+    fn frame(&mut self, operations: &mut [Operation<'_, u8>]) {
+        self.settle();
+        if self.sleeping {
+            // the falling edge of CS wakes the device
+            self.sleeping = false;
+            self.memory[STATUS_REGISTER as usize] = STATUS_DONE;
+        }
+        let mut frame = Frame::default();
+        for operation in operations {
+            match operation {
+                Operation::Read(words) => words.iter_mut().for_each(|word| *word = self.shift(&mut frame, 0)),
+                Operation::Write(words) => words.iter().for_each(|word| { self.shift(&mut frame, *word); }),
+                Operation::Transfer(read, write) => {
+                    // bytes beyond the end of `write` are clocked out as zeros
+                    for i in 0..read.len().max(write.len()) {
+                        let miso = self.shift(&mut frame, write.get(i).copied().unwrap_or(0));
+                        if let Some(word) = read.get_mut(i) {
+                            *word = miso;
+                        }
+                    }
+                }
+                Operation::TransferInPlace(words) => words.iter_mut().for_each(|word| *word = self.shift(&mut frame, *word)),
+                Operation::DelayNs(_) => {}
+            }
+        }
+        self.clock.advance_ns(frame.length as u64 * SPI_BYTE_TIME_NS);
+        // commands are executed once CS is released
+        if let Some(command) = frame.command {
+            self.execute(command);
+        }
+    }
+
+    //clock a single byte through the device, returns the byte the device outputs
+    fn shift(&mut self, frame: &mut Frame, mosi: u8) -> u8 {
+        frame.length += 1;
+        if frame.header_length < frame.header.len() {
+            frame.header[frame.header_length] = mosi;
+            frame.header_length += 1;
+            frame.address = u16::from_be_bytes([frame.header[1], frame.header[2]]) as usize;
+            return 0;
+        }
+        let address = frame.address;
+        frame.address += 1;
+        if address >= MEMORY_SIZE {
+            return 0;
+        }
+        match frame.header[0] {
+            LTC2983_READ => self.memory[address],
+            LTC2983_WRITE => {
+                self.memory[address] = mosi;
+                if address == STATUS_REGISTER as usize {
+                    frame.command = Some(mosi);
+                }
+                0
+            }
+            // other instructions are ignored
+            _ => 0
+        }
+    }
+}
+
+//progress through the instruction and address bytes at the start of every frame
+#[derive(Default)]
+struct Frame {
+    header: [u8; 3],
+    header_length: usize,
+    address: usize,
+    // bytes clocked through the device
+    length: usize,
+    command: Option<u8>
+}
+
+//conversion cycles of a sensor, rotating the excitation, the lead compensation of 3 wire RTDs and a third diode
+//reading take an extra cycle
+fn sensor_cycles(probe: &ThermalProbeType) -> u64 {
+    match probe {
+        ThermalProbeType::RTD_PT10(param)   |
+        ThermalProbeType::RTD_PT50(param)   |
+        ThermalProbeType::RTD_PT100(param)  |
+        ThermalProbeType::RTD_PT200(param)  |
+        ThermalProbeType::RTD_PT500(param)  |
+        ThermalProbeType::RTD_PT1000(param) |
+        ThermalProbeType::RTD_1000(param)   |
+        ThermalProbeType::RTD_NI120(param)  |
+        ThermalProbeType::RTD_Custom(param, _) if param.sensor_configuration.current_source_rotation
+            || param.sensor_configuration.wire_cnt == RTDWireCount::Wire3 => 3,
+        ThermalProbeType::Thermistor_44004_44033(param) |
+        ThermalProbeType::Thermistor_44005_44030(param) |
+        ThermalProbeType::Thermistor_44007_44034(param) |
+        ThermalProbeType::Thermistor_44006_44031(param) |
+        ThermalProbeType::Thermistor_44008_44032(param) |
+        ThermalProbeType::Thermistor_YSI400(param)      |
+        ThermalProbeType::Thermistor_Spectrum(param)    |
+        ThermalProbeType::Thermistor_Custom_SteinhartHart(param, _) |
+        ThermalProbeType::Thermistor_Custom_Table(param, _) if param.excitation_mode == ThermistorExcitationMode::SharingRotation => 3,
+        ThermalProbeType::Diode(param) if param.num_reading == DiodeReadingCount::READ3 => 3,
+        _ => 2
+    }
+}
+
+//cold junction channel of thermocouples, `None` for all other sensor types
+fn thermocouple_cold_junction(probe: &ThermalProbeType) -> Option<Option<LTC2983Channel>> {
+    match probe {
+        ThermalProbeType::Thermocouple_J(param) |
+        ThermalProbeType::Thermocouple_K(param) |
+        ThermalProbeType::Thermocouple_E(param) |
+        ThermalProbeType::Thermocouple_N(param) |
+        ThermalProbeType::Thermocouple_R(param) |
+        ThermalProbeType::Thermocouple_S(param) |
+        ThermalProbeType::Thermocouple_T(param) |
+        ThermalProbeType::Thermocouple_B(param) |
+        ThermalProbeType::Thermocouple_Custom(param, _) => Some(param.cold_junction_channel),
+        _ => None
+    }
+}
+
+//custom table entries as points of a model, `point` maps each entry to (x, y)
+fn table_points(entries: &[(f32, f32)], point: impl Fn(f64, f64) -> (f64, f64)) -> Vec<(f64, f64)> {
+    entries.iter().map(|(a, b)| point(*a as f64, *b as f64)).collect()
+}
+
+//result word with the fault byte, results without hard faults carry the valid bit, values outside
+//of the 24 bit range saturate
+fn encode_result<Frac: fixed::types::extra::LeEqU32>(value: f64, faults: FaultFlags) -> u32 {
+    let max = ((1 << 23) - 1) as f64 / (1 << Frac::U32) as f64;
+    let value = to_fixed_i24::<Frac>(value.clamp(-max, max) as f32).unwrap_or(0);
+    let faults = if faults.is_hard_fault() { faults } else { faults | FaultFlags::VALID };
+    ((faults.bits() as u32) << 24) | value
+}
+
+impl ErrorType for SimulatedLTC2983 {
+    type Error = ErrorKind;
+}
+
+impl SpiDevice for SimulatedLTC2983 {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        if self.spi_fault(operations) {
+            return Err(ErrorKind::Other);
+        }
+        self.frame(operations);
+        Ok(())
+    }
+}
+
+impl embedded_hal_async::spi::SpiDevice for SimulatedLTC2983 {
+    async fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        SpiDevice::transaction(self, operations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{future::Future, pin::pin, task::{Context, Poll, Waker}};
+
+    use crate::{
+        asynch, ConfigChange, CustomThermistorTable, DiodeParameters, LTC2983Error, DiodeReadingCount, FaultFlags, FilterNotch, GlobalConfig,
+        LTC2983, LTC2983Channel, LTC2983Config, LTC2983Result, LTC2984Variant, RTDCurve, RTDParameters, RTDSensorConfiguration, RTDWireCount,
+        SensorConfiguration, TemperatureUnit, ThermalProbeType, ThermistorParameters, ThermocoupleParameters,
+    };
+
+    use super::*;
+
+    //INTERRUPT pin that either signals the end of the conversion right away or never
+    struct MockPin(bool);
+
+    impl embedded_hal::digital::ErrorType for MockPin {
+        type Error = core::convert::Infallible;
+    }
+
+    impl embedded_hal::digital::InputPin for MockPin {
+        fn is_high(&mut self) -> Result<bool, Self::Error> {
+            Ok(self.0)
+        }
+
+        fn is_low(&mut self) -> Result<bool, Self::Error> {
+            Ok(!self.0)
+        }
+    }
+
+    impl embedded_hal_async::digital::Wait for MockPin {
+        async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+            if !self.0 {
+                core::future::pending::<()>().await;
+            }
+            Ok(())
+        }
+
+        async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+            if self.0 {
+                core::future::pending::<()>().await;
+            }
+            Ok(())
+        }
+
+        async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+            core::future::pending().await
+        }
+
+        async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+            core::future::pending().await
+        }
+
+        async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+            core::future::pending().await
+        }
+    }
+
+    //the simulator never blocks, so every future completes on the first poll
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("simulated transaction did not complete")
+        }
+    }
+
+    #[test]
+    fn test_multi_conversion() {
+        let mut sim = SimulatedLTC2983::new();
+        sim.set_temperature(LTC2983Channel::CH2, 23.25);
+        sim.set_temperature(LTC2983Channel::CH4, -40.5);
+        sim.set_temperature(LTC2983Channel::CH5, 250.);
+        let mut clock = sim.clock();
+        let mut ltc = LTC2983::new(sim);
+
+        ltc.setup_channel(ThermalProbeType::Diode(DiodeParameters::default()), &LTC2983Channel::CH2).unwrap();
+        let thermocouple = || ThermocoupleParameters::default().cold_junction(LTC2983Channel::CH2);
+        ltc.setup_channel(ThermalProbeType::Thermocouple_K(thermocouple()), &LTC2983Channel::CH4).unwrap();
+        ltc.setup_channel(ThermalProbeType::Thermocouple_T(thermocouple()), &LTC2983Channel::CH5).unwrap();
+
+        let channels = [LTC2983Channel::CH2, LTC2983Channel::CH4, LTC2983Channel::CH5];
+        ltc.start_multi_conversion(&channels).unwrap();
+        ltc.wait_for_completion(&mut clock).unwrap();
+        let results = ltc.read_multi_temperature(&channels).map(|res| res.unwrap().result());
+        assert_eq!(results, [LTC2983Result::Valid(23.25), LTC2983Result::Valid(-40.5), LTC2983Result::Valid(250.)]);
+
+        // only the channels of the mask were converted
+        assert!(!matches!(ltc.read_temperature(&LTC2983Channel::CH3).unwrap().result(), LTC2983Result::Valid(_)));
+    }
+
+    fn converted<SPI: embedded_hal::spi::SpiDevice>(ltc: &mut LTC2983<SPI>, clock: &mut VirtualClock, channel: LTC2983Channel) -> LTC2983Result {
+        ltc.start_conversion(&channel).unwrap();
+        ltc.wait_for_completion(clock).unwrap();
+        ltc.read_temperature(&channel).unwrap().result()
+    }
+
+    fn valid(result: LTC2983Result) -> f32 {
+        match result {
+            LTC2983Result::Valid(temperature) => temperature,
+            result => panic!("unexpected result {result:?}")
+        }
+    }
+
+    #[test]
+    fn test_sensor_models() {
+        let mut sim = SimulatedLTC2983::new();
+        sim.set_temperature(LTC2983Channel::CH3, 31.5);
+        sim.set_temperature(LTC2983Channel::CH4, 812.);
+        sim.set_temperature(LTC2983Channel::CH6, -12.);
+        sim.set_temperature(LTC2983Channel::CH7, 65.);
+        sim.set_temperature(LTC2983Channel::CH9, 200.);
+        let mut clock = sim.clock();
+        let mut ltc = LTC2983::new(sim);
+
+        // thermocouple with a PT100 at the cold junction
+        ltc.setup_channel(ThermalProbeType::SenseResistor(2000.), &LTC2983Channel::CH2).unwrap();
+        let rtd = RTDParameters::default().channel(LTC2983Channel::CH2).curve(RTDCurve::American);
+        ltc.setup_channel(ThermalProbeType::RTD_PT100(rtd), &LTC2983Channel::CH3).unwrap();
+        let thermocouple = ThermocoupleParameters::default().cold_junction(LTC2983Channel::CH3);
+        ltc.setup_channel(ThermalProbeType::Thermocouple_K(thermocouple), &LTC2983Channel::CH4).unwrap();
+        ltc.setup_channel(ThermalProbeType::SenseResistor(10000.), &LTC2983Channel::CH5).unwrap();
+        ltc.setup_channel(ThermalProbeType::Thermistor_44006_44031(ThermistorParameters::default().channel(LTC2983Channel::CH5)), &LTC2983Channel::CH6).unwrap();
+        let diode = DiodeParameters::default().ideality_factor(1.02).num_reading(DiodeReadingCount::READ3);
+        ltc.setup_channel(ThermalProbeType::Diode(diode), &LTC2983Channel::CH7).unwrap();
+        ltc.setup_channel(ThermalProbeType::Thermistor_44006_44031(ThermistorParameters::default().channel(LTC2983Channel::CH5)), &LTC2983Channel::CH9).unwrap();
+
+        assert!((valid(converted(&mut ltc, &mut clock, LTC2983Channel::CH3)) - 31.5).abs() < 1e-2);
+        assert!((valid(converted(&mut ltc, &mut clock, LTC2983Channel::CH4)) - 812.).abs() < 1e-2);
+        assert!((valid(converted(&mut ltc, &mut clock, LTC2983Channel::CH6)) + 12.).abs() < 1e-2);
+        assert!((valid(converted(&mut ltc, &mut clock, LTC2983Channel::CH7)) - 65.).abs() < 1e-2);
+        // the thermistor curve ends at 150°C
+        assert_eq!(converted(&mut ltc, &mut clock, LTC2983Channel::CH9), LTC2983Result::Suspect(150., FaultFlags::VALID | FaultFlags::SENSOR_OVER_RANGE));
+
+        // a cold junction channel without a sensor makes thermocouple results invalid
+        let thermocouple = ThermocoupleParameters::default().cold_junction(LTC2983Channel::CH8);
+        ltc.setup_channel(ThermalProbeType::Thermocouple_J(thermocouple), &LTC2983Channel::CH4).unwrap();
+        assert_eq!(converted(&mut ltc, &mut clock, LTC2983Channel::CH4), LTC2983Result::Invalid(FaultFlags::CJ_HARD_FAULT));
+
+        // resistive sensors without a sense resistor at their rsense channel fail
+        ltc.setup_channel(ThermalProbeType::Thermistor_44006_44031(ThermistorParameters::default().channel(LTC2983Channel::CH8)), &LTC2983Channel::CH9).unwrap();
+        assert_eq!(converted(&mut ltc, &mut clock, LTC2983Channel::CH9), LTC2983Result::Invalid(FaultFlags::SENSOR_HARD_FAULT));
+        let rtd = RTDParameters::default().channel(LTC2983Channel::CH7);
+        ltc.setup_channel(ThermalProbeType::RTD_PT100(rtd), &LTC2983Channel::CH3).unwrap();
+        assert_eq!(converted(&mut ltc, &mut clock, LTC2983Channel::CH3), LTC2983Result::Invalid(FaultFlags::SENSOR_HARD_FAULT));
+    }
+
+    #[test]
+    fn test_sensor_noise() {
+        let run = |seed| {
+            let mut sim = SimulatedLTC2983::new();
+            sim.set_noise(SensorNoise::default().rtd(0.05));
+            sim.set_seed(seed);
+            sim.set_temperature(LTC2983Channel::CH3, 20.);
+            let mut clock = sim.clock();
+            let mut ltc = LTC2983::new(sim);
+            ltc.setup_channel(ThermalProbeType::SenseResistor(2000.), &LTC2983Channel::CH2).unwrap();
+            ltc.setup_channel(ThermalProbeType::RTD_PT1000(RTDParameters::default()), &LTC2983Channel::CH3).unwrap();
+            (0..200).map(|_| valid(converted(&mut ltc, &mut clock, LTC2983Channel::CH3))).collect::<Vec<_>>()
+        };
+        let readings = run(7);
+        assert_eq!(readings, run(7));
+        assert_ne!(readings, run(8));
+
+        // 0.05Ω on a PT1000 is about 0.013°C
+        let mean = readings.iter().sum::<f32>() / readings.len() as f32;
+        let deviation = (readings.iter().map(|t| (t - mean).powi(2)).sum::<f32>() / readings.len() as f32).sqrt();
+        assert!((mean - 20.).abs() < 5e-3);
+        assert!(deviation > 0.008 && deviation < 0.02);
+    }
+
+    #[test]
+    fn test_fault_injection() {
+        let mut sim = SimulatedLTC2983::new();
+        sim.set_temperature(LTC2983Channel::CH3, 30.);
+        sim.set_temperature(LTC2983Channel::CH4, 400.);
+        let mut clock = sim.clock();
+        let mut ltc = LTC2983::new(&mut sim);
+        ltc.setup_channel(ThermalProbeType::SenseResistor(2000.), &LTC2983Channel::CH2).unwrap();
+        ltc.setup_channel(ThermalProbeType::RTD_PT100(RTDParameters::default()), &LTC2983Channel::CH3).unwrap();
+        let thermocouple = ThermocoupleParameters::default().cold_junction(LTC2983Channel::CH3);
+        ltc.setup_channel(ThermalProbeType::Thermocouple_K(thermocouple), &LTC2983Channel::CH4).unwrap();
+
+        // a broken cold junction sensor fails the thermocouple as well
+        sim.inject_fault(LTC2983Channel::CH3, Fault::OpenSensor);
+        assert_eq!(converted(&mut LTC2983::new(&mut sim), &mut clock, LTC2983Channel::CH4), LTC2983Result::Invalid(FaultFlags::CJ_HARD_FAULT));
+        assert_eq!(converted(&mut LTC2983::new(&mut sim), &mut clock, LTC2983Channel::CH3), LTC2983Result::Invalid(FaultFlags::SENSOR_HARD_FAULT));
+        sim.clear_faults(LTC2983Channel::CH3);
+        assert!((valid(converted(&mut LTC2983::new(&mut sim), &mut clock, LTC2983Channel::CH3)) - 30.).abs() < 1e-2);
+
+        sim.inject_faults(LTC2983Channel::CH3, Fault::ShortedSensor, 1);
+        let shorted = FaultFlags::SENSOR_HARD_FAULT | FaultFlags::SENSOR_UNDER_RANGE;
+        assert_eq!(converted(&mut LTC2983::new(&mut sim), &mut clock, LTC2983Channel::CH3), LTC2983Result::Invalid(shorted));
+        sim.inject_faults(LTC2983Channel::CH4, Fault::ShortedSensor, 1);
+        assert!((valid(converted(&mut LTC2983::new(&mut sim), &mut clock, LTC2983Channel::CH4)) - 30.).abs() < 1e-2);
+
+        sim.inject_faults(LTC2983Channel::CH4, Fault::AdcOutOfRange, 1);
+        match converted(&mut LTC2983::new(&mut sim), &mut clock, LTC2983Channel::CH4) {
+            LTC2983Result::Suspect(temperature, faults) => {
+                assert!((temperature - 400.).abs() < 1e-2);
+                assert_eq!(faults, FaultFlags::VALID | FaultFlags::ADC_OUT_OF_RANGE);
+            }
+            result => panic!("unexpected result {result:?}")
+        }
+        sim.inject_faults(LTC2983Channel::CH4, Fault::ColdJunctionHardFault, 1);
+        assert_eq!(converted(&mut LTC2983::new(&mut sim), &mut clock, LTC2983Channel::CH4), LTC2983Result::Invalid(FaultFlags::CJ_HARD_FAULT));
+
+        sim.inject_faults(LTC2983Channel::CH4, Fault::SpiError, 1);
+        let mut ltc = LTC2983::new(&mut sim);
+        assert!(matches!(ltc.read_temperature(&LTC2983Channel::CH4), Err(LTC2983Error::SpiError(ErrorKind::Other))));
+        assert!((valid(converted(&mut ltc, &mut clock, LTC2983Channel::CH4)) - 400.).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_fault_retries() {
+        let mut sim = SimulatedLTC2983::new();
+        sim.set_temperature(LTC2983Channel::CH5, 42.);
+        let mut clock = sim.clock();
+        LTC2983::new(&mut sim).setup_channel(ThermalProbeType::Diode(DiodeParameters::default()), &LTC2983Channel::CH5).unwrap();
+
+        sim.inject_faults(LTC2983Channel::CH5, Fault::OpenSensor, 3);
+        let average = LTC2983::new(&mut sim).get_temperature_avg(&LTC2983Channel::CH5, 2, &mut clock).unwrap();
+        assert!((average - 42.).abs() < 1e-2);
+
+        // failed readouts are repeated as well
+        sim.inject_faults(LTC2983Channel::CH5, Fault::SpiError, 3);
+        let average = LTC2983::new(&mut sim).get_temperature_avg(&LTC2983Channel::CH5, 2, &mut clock).unwrap();
+        assert!((average - 42.).abs() < 1e-2);
+
+        sim.inject_faults(LTC2983Channel::CH5, Fault::AdcOutOfRange, 4);
+        let average = LTC2983::new(&mut sim).get_temperature_avg(&LTC2983Channel::CH5, 2, &mut clock);
+        assert!(matches!(average, Err(LTC2983Error::RetriesExhausted(3))));
+
+        sim.inject_faults(LTC2983Channel::CH5, Fault::StuckDoneBit, 1);
+        let mut ltc = LTC2983::new(&mut sim);
+        assert!(matches!(ltc.get_temperature_avg(&LTC2983Channel::CH5, 2, &mut clock), Err(LTC2983Error::ConversionTimeout)));
+        assert!(!ltc.status().unwrap().done());
+        assert!((ltc.get_temperature_avg(&LTC2983Channel::CH5, 2, &mut clock).unwrap() - 42.).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_conversion_timing() {
+        let mut sim = SimulatedLTC2983::new();
+        let clock = sim.clock();
+        let mut ltc = LTC2983::new(&mut sim);
+        ltc.setup_channel(ThermalProbeType::SenseResistor(2000.), &LTC2983Channel::CH2).unwrap();
+        ltc.setup_channel(ThermalProbeType::RTD_PT100(RTDParameters::default()), &LTC2983Channel::CH3).unwrap();
+        let rotation = RTDSensorConfiguration::default().wire_cnt(RTDWireCount::Wire4).current_source_rotation(true);
+        ltc.setup_channel(ThermalProbeType::RTD_PT100(RTDParameters::default().sensor_configuration(rotation)), &LTC2983Channel::CH4).unwrap();
+        let diode = DiodeParameters::default().num_reading(DiodeReadingCount::READ3);
+        ltc.setup_channel(ThermalProbeType::Diode(diode), &LTC2983Channel::CH5).unwrap();
+        let thermocouple = ThermocoupleParameters::default().cold_junction(LTC2983Channel::CH5);
+        ltc.setup_channel(ThermalProbeType::Thermocouple_T(thermocouple), &LTC2983Channel::CH6).unwrap();
+        let three_wire = RTDSensorConfiguration::default().wire_cnt(RTDWireCount::Wire3);
+        ltc.setup_channel(ThermalProbeType::RTD_PT100(RTDParameters::default().sensor_configuration(three_wire)), &LTC2983Channel::CH7).unwrap();
+
+        // a 3 wire RTD is still converting after two cycles
+        ltc.start_conversion(&LTC2983Channel::CH7).unwrap();
+        clock.advance_us(2 * 82_000);
+        assert!(!ltc.status().unwrap().done());
+        clock.advance_us(82_000);
+        assert!(ltc.status().unwrap().done());
+
+        // results only appear once the conversion is done
+        ltc.start_conversion(&LTC2983Channel::CH3).unwrap();
+        clock.advance_us(150_000);
+        assert!(!matches!(ltc.read_temperature(&LTC2983Channel::CH3).unwrap().result(), LTC2983Result::Valid(_)));
+        let mut delay = clock.clone();
+        assert!(matches!(ltc.wait_done(&mut delay, 10_000), Err(LTC2983Error::ConversionTimeout)));
+        ltc.wait_done(&mut delay, 10_000).unwrap();
+        assert_eq!(ltc.read_temperature(&LTC2983Channel::CH3).unwrap().result(), LTC2983Result::Valid(25.));
+
+        // the status reports the conversion as running until the clock passes its duration
+        let duration_us = |ltc: &mut LTC2983<&mut SimulatedLTC2983>, channels: &[LTC2983Channel]| {
+            ltc.start_multi_conversion(channels).unwrap();
+            let start = clock.now_us();
+            let mut polls = 0;
+            while !ltc.status().unwrap().done() {
+                clock.advance_us(100);
+                polls += 1;
+            }
+            assert!(polls > 0);
+            clock.now_us() - start
+        };
+        let approximately = |duration: u64, expected: u64| (expected..expected + 200).contains(&duration);
+        assert!(approximately(duration_us(&mut ltc, &[LTC2983Channel::CH3]), 164_000));
+        assert!(approximately(duration_us(&mut ltc, &[LTC2983Channel::CH4]), 246_000));
+        assert!(approximately(duration_us(&mut ltc, &[LTC2983Channel::CH5]), 246_000));
+        assert!(approximately(duration_us(&mut ltc, &[LTC2983Channel::CH7]), 246_000));
+        // the thermocouple waits for the three readings of its cold junction diode
+        assert!(approximately(duration_us(&mut ltc, &[LTC2983Channel::CH6]), 246_000));
+        assert!(approximately(duration_us(&mut ltc, &[LTC2983Channel::CH2, LTC2983Channel::CH3, LTC2983Channel::CH4]), 410_000));
+        ltc.set_mux_delay(5_000).unwrap();
+        assert!(approximately(duration_us(&mut ltc, &[LTC2983Channel::CH3, LTC2983Channel::CH4]), 420_000));
+
+    }
+
+    #[test]
+    fn test_single_conversion() {
+        let mut sim = SimulatedLTC2983::new();
+        sim.set_temperature(LTC2983Channel::CH1, 100.);
+        sim.set_voltage(LTC2983Channel::CH3, -0.125);
+        let mut clock = sim.clock();
+        let mut ltc = LTC2983::new(sim);
+
+        ltc.write_global_config(&GlobalConfig::default().temperature_unit(TemperatureUnit::Fahrenheit)).unwrap();
+        ltc.setup_channel(ThermalProbeType::Diode(DiodeParameters::default()), &LTC2983Channel::CH1).unwrap();
+        ltc.setup_channel(ThermalProbeType::DirectADC(SensorConfiguration::SingleEnded), &LTC2983Channel::CH3).unwrap();
+
+        ltc.start_conversion(&LTC2983Channel::CH3).unwrap();
+        let status = ltc.status().unwrap();
+        assert!(!status.done() && status.start());
+        ltc.wait_for_completion(&mut clock).unwrap();
+        let status = ltc.status().unwrap();
+        assert!(status.done() && !status.start());
+        assert_eq!(ltc.read_voltage(&LTC2983Channel::CH3).unwrap(), LTC2983Result::Valid(-0.125));
+
+        ltc.start_conversion(&LTC2983Channel::CH1).unwrap();
+        ltc.wait_for_completion(&mut clock).unwrap();
+        let reading = ltc.read_temperature(&LTC2983Channel::CH1).unwrap();
+        assert_eq!((reading.result(), reading.unit()), (LTC2983Result::Valid(212.), TemperatureUnit::Fahrenheit));
+        assert_eq!(ltc.read_channel_config(&LTC2983Channel::CH1).unwrap(), ThermalProbeType::Diode(DiodeParameters::default()));
+    }
+
+    #[test]
+    fn test_config_apply() {
+        let mut sim = SimulatedLTC2983::new();
+        let thermistor = |entries: &[(f32, f32)]| ThermalProbeType::Thermistor_Custom_Table(
+            ThermistorParameters::default().custom_address(0x250),
+            CustomThermistorTable::new(entries).unwrap()
+        );
+        let config = |entries: &[(f32, f32)]| LTC2983Config::default()
+            .global_config(GlobalConfig::default().filter_notch(FilterNotch::Reject50Hz))
+            .channel(LTC2983Channel::CH2, ThermalProbeType::SenseResistor(2000.))
+            .channel(LTC2983Channel::CH3, ThermalProbeType::RTD_PT100(RTDParameters::default()))
+            .channel(LTC2983Channel::CH5, thermistor(entries));
+        let table = [(100., 400.), (1000., 300.), (10000., 250.)];
+
+        let mut ltc = LTC2983::new(&mut sim);
+        assert_eq!(config(&table).apply(&mut ltc).unwrap(), [
+            ConfigChange::GlobalConfig,
+            ConfigChange::ChannelAssignment(LTC2983Channel::CH2),
+            ConfigChange::ChannelAssignment(LTC2983Channel::CH3),
+            ConfigChange::ChannelAssignment(LTC2983Channel::CH5),
+            ConfigChange::CustomData(LTC2983Channel::CH5),
+        ]);
+        assert_eq!(ltc.read_channel_config(&LTC2983Channel::CH5).unwrap(), thermistor(&table));
+
+        // applying the same configuration again writes nothing
+        assert!(config(&table).diff(&mut ltc).unwrap().is_empty());
+        let memory = sim.memory().to_vec();
+        let mut ltc = LTC2983::new(&mut sim);
+        assert!(config(&table).apply(&mut ltc).unwrap().is_empty());
+        assert_eq!(sim.memory(), memory);
+
+        // a longer table changes the size in the channel word and the custom data
+        let table = [(100., 400.), (1000., 300.), (10000., 250.), (100000., 200.)];
+        let mut ltc = LTC2983::new(&mut sim);
+        assert_eq!(config(&table).diff(&mut ltc).unwrap(), [
+            ConfigChange::ChannelAssignment(LTC2983Channel::CH5),
+            ConfigChange::CustomData(LTC2983Channel::CH5),
+        ]);
+        config(&table).apply(&mut ltc).unwrap();
+        assert_eq!(ltc.read_channel_config(&LTC2983Channel::CH5).unwrap(), thermistor(&table));
+
+        // comparing leaves the unit the driver labels results with untouched
+        LTC2983::new(&mut sim).write_global_config(&GlobalConfig::default().temperature_unit(TemperatureUnit::Fahrenheit)).unwrap();
+        let mut ltc = LTC2983::new(&mut sim);
+        assert_eq!(config(&table).diff(&mut ltc).unwrap(), [ConfigChange::GlobalConfig]);
+        assert_eq!(ltc.temperature_unit(), TemperatureUnit::Celsius);
+    }
+
+    #[test]
+    fn test_sleep_and_eeprom() {
+        let sim = SimulatedLTC2983::new();
+        let mut clock = sim.clock();
+        let mut ltc = LTC2983::with_variant(sim, LTC2984Variant);
+        ltc.setup_channel(ThermalProbeType::Diode(DiodeParameters::default()), &LTC2983Channel::CH7).unwrap();
+        ltc.save_to_eeprom(&mut clock).unwrap();
+        ltc.setup_channel(ThermalProbeType::DirectADC(SensorConfiguration::SingleEnded), &LTC2983Channel::CH7).unwrap();
+
+        ltc.sleep().unwrap();
+        ltc.wake(&mut clock).unwrap();
+        ltc.restore_from_eeprom(&mut clock).unwrap();
+        assert_eq!(ltc.read_channel_config(&LTC2983Channel::CH7).unwrap(), ThermalProbeType::Diode(DiodeParameters::default()));
+    }
+
+    #[test]
+    fn test_interrupt_pin() {
+        let mut sim = SimulatedLTC2983::new();
+        let mut clock = sim.clock();
+        LTC2983::new(&mut sim).setup_channel(ThermalProbeType::Diode(DiodeParameters::default()), &LTC2983Channel::CH5).unwrap();
+
+        // the status register never reports done, only the pin ends the waits
+        sim.inject_fault(LTC2983Channel::CH5, Fault::StuckDoneBit);
+        let mut ltc = LTC2983::new(&mut sim).with_interrupt_pin(MockPin(true));
+        ltc.start_conversion(&LTC2983Channel::CH5).unwrap();
+        ltc.wait_for_completion(&mut clock).unwrap();
+        ltc.wait_done(&mut clock, 10_000).unwrap();
+        let mut ltc = LTC2983::new(&mut sim).with_interrupt_pin(MockPin(false));
+        assert!(matches!(ltc.wait_done(&mut clock, 10_000), Err(LTC2983Error::ConversionTimeout)));
+
+        let mut ltc = asynch::LTC2983::new(&mut sim, clock.clone()).with_interrupt_pin(MockPin(true));
+        block_on(ltc.start_conversion(&LTC2983Channel::CH5)).unwrap();
+        block_on(ltc.wait_for_completion()).unwrap();
+        block_on(ltc.wait_done(10_000)).unwrap();
+        let mut ltc = asynch::LTC2983::new(&mut sim, clock.clone()).with_interrupt_pin(MockPin(false));
+        assert!(matches!(block_on(ltc.wait_done(10_000)), Err(LTC2983Error::ConversionTimeout)));
+    }
+
+    #[test]
+    fn test_async_retries() {
+        let mut sim = SimulatedLTC2983::new();
+        sim.set_temperature(LTC2983Channel::CH5, 42.);
+        let clock = sim.clock();
+        let mut ltc = asynch::LTC2983::new(&mut sim, clock.clone());
+        block_on(ltc.setup_channel(ThermalProbeType::Diode(DiodeParameters::default()), &LTC2983Channel::CH5)).unwrap();
+        block_on(ltc.start_conversion(&LTC2983Channel::CH5)).unwrap();
+        assert!(!block_on(ltc.status()).unwrap().done());
+        block_on(ltc.wait_for_completion()).unwrap();
+        assert_eq!(block_on(ltc.read_temperature(&LTC2983Channel::CH5)).unwrap().result(), LTC2983Result::Valid(42.));
+
+        sim.inject_faults(LTC2983Channel::CH5, Fault::OpenSensor, 3);
+        let average = block_on(asynch::LTC2983::new(&mut sim, clock.clone()).get_temperature_avg(&LTC2983Channel::CH5, 2)).unwrap();
+        assert!((average - 42.).abs() < 1e-2);
+
+        // a failed readout only fails the result of its channel
+        sim.inject_faults(LTC2983Channel::CH5, Fault::SpiError, 1);
+        let results = block_on(asynch::LTC2983::new(&mut sim, clock.clone()).read_multi_temperature(&[LTC2983Channel::CH4, LTC2983Channel::CH5]));
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(LTC2983Error::SpiError(ErrorKind::Other))));
+
+        sim.inject_faults(LTC2983Channel::CH5, Fault::AdcOutOfRange, 4);
+        let average = block_on(asynch::LTC2983::new(&mut sim, clock.clone()).get_temperature_avg(&LTC2983Channel::CH5, 2));
+        assert!(matches!(average, Err(LTC2983Error::RetriesExhausted(3))));
+    }
+
+    #[test]
+    fn test_async_driver() {
+        let mut sim = SimulatedLTC2983::new();
+        sim.set_temperature(LTC2983Channel::CH8, 36.5);
+        sim.set_temperature(LTC2983Channel::CH9, 37.);
+        let clock = sim.clock();
+        let mut ltc = asynch::LTC2983::new(sim, clock.clone());
+
+        let channels = [LTC2983Channel::CH8, LTC2983Channel::CH9];
+        for channel in &channels {
+            block_on(ltc.setup_channel(ThermalProbeType::Diode(DiodeParameters::default()), channel)).unwrap();
+        }
+        assert_eq!(block_on(ltc.get_multi_temperature_avg(&channels, 4)).unwrap(), [36.5, 37.]);
+        // four rounds of two diodes with two conversion cycles each, polled every 10ms
+        assert!((1_312_000..1_352_000).contains(&clock.now_us()));
+    }
+}
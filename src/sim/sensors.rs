@@ -0,0 +1,304 @@
+//! Physical models of the sensors connected to the simulated device
+//!
+//! Every model maps the temperature of the sensor to the quantity the device measures. The device
+//! side of the simulation reverses the same models with [`invert`], restricted to the temperature
+//! range the device linearizes the sensor type over.
+
+use crate::{FaultFlags, RTDCurve};
+
+pub(super) const ZERO_CELSIUS: f64 = 273.15;
+const BOLTZMANN: f64 = 1.380649e-23;
+const ELEMENTARY_CHARGE: f64 = 1.602176634e-19;
+// saturation current of the simulated sense diodes
+const DIODE_SATURATION_CURRENT: f64 = 1e-14;
+
+/// NIST ITS-90 reference function of a thermocouple type
+///
+/// E = sum(c_i * t^i) + a0 * exp(a1 * (t - a2)^2), with E in mV and t in °C
+pub(super) struct Thermocouple {
+    // polynomial coefficients together with the upper end of the temperature range they cover
+    segments: &'static [(f64, &'static [f64])],
+    exponential: Option<[f64; 3]>,
+    pub(super) range: (f64, f64)
+}
+
+impl Thermocouple {
+    /// thermoelectric voltage in mV with the reference junction at 0°C
+    pub(super) fn emf(&self, celsius: f64) -> f64 {
+        let (_, coefficients) = self.segments.iter()
+            .find(|(upper, _)| celsius <= *upper)
+            .unwrap_or(&self.segments[self.segments.len() - 1]);
+        let mut emf = polynomial(coefficients, celsius);
+        if let Some([a0, a1, a2]) = self.exponential {
+            if celsius > 0. {
+                emf += a0 * (a1 * (celsius - a2).powi(2)).exp();
+            }
+        }
+        emf
+    }
+}
+
+pub(super) const THERMOCOUPLE_J: Thermocouple = Thermocouple {
+    segments: &[
+        (760., &[0., 5.0381187815e-2, 3.0475836930e-5, -8.5681065720e-8, 1.3228195295e-10, -1.7052958337e-13,
+                 2.0948090697e-16, -1.2538395336e-19, 1.5631725697e-23]),
+        (1200., &[2.9645625681e2, -1.4976127786, 3.1787103924e-3, -3.1847686701e-6, 1.5720819004e-9,
+                  -3.0691369056e-13])
+    ],
+    exponential: None,
+    range: (-210., 1200.)
+};
+
+pub(super) const THERMOCOUPLE_K: Thermocouple = Thermocouple {
+    segments: &[
+        (0., &[0., 3.9450128025e-2, 2.3622373598e-5, -3.2858906784e-7, -4.9904828777e-9, -6.7509059173e-11,
+               -5.7410327428e-13, -3.1088872894e-15, -1.0451609365e-17, -1.9889266878e-20, -1.6322697486e-23]),
+        (1372., &[-1.7600413686e-2, 3.8921204975e-2, 1.8558770032e-5, -9.9457592874e-8, 3.1840945719e-10,
+                  -5.6072844889e-13, 5.6075059059e-16, -3.2020720003e-19, 9.7151147152e-23, -1.2104721275e-26])
+    ],
+    exponential: Some([1.185976e-1, -1.183432e-4, 1.269686e2]),
+    range: (-200., 1372.)
+};
+
+pub(super) const THERMOCOUPLE_E: Thermocouple = Thermocouple {
+    segments: &[
+        (0., &[0., 5.8665508708e-2, 4.5410977124e-5, -7.7998048686e-7, -2.5800160843e-8, -5.9452583057e-10,
+               -9.3214058667e-12, -1.0287605534e-13, -8.0370123621e-16, -4.3979497391e-18, -1.6414776355e-20,
+               -3.9673619516e-23, -5.5827328721e-26, -3.4657842013e-29]),
+        (1000., &[0., 5.8665508710e-2, 4.5032275582e-5, 2.8908407212e-8, -3.3056896652e-10, 6.5024403270e-13,
+                  -1.9197495504e-16, -1.2536600497e-18, 2.1489217569e-21, -1.4388041782e-24, 3.5960899481e-28])
+    ],
+    exponential: None,
+    range: (-200., 1000.)
+};
+
+pub(super) const THERMOCOUPLE_N: Thermocouple = Thermocouple {
+    segments: &[
+        (0., &[0., 2.6159105962e-2, 1.0957484228e-5, -9.3841111554e-8, -4.6412039759e-11, -2.6303357716e-12,
+               -2.2653438003e-14, -7.6089300791e-17, -9.3419667835e-20]),
+        (1300., &[0., 2.5929394601e-2, 1.5710141880e-5, 4.3825627237e-8, -2.5261169794e-10, 6.4311819339e-13,
+                  -1.0063471519e-15, 9.9745338992e-19, -6.0863245607e-22, 2.0849229339e-25, -3.0682196151e-29])
+    ],
+    exponential: None,
+    range: (-200., 1300.)
+};
+
+pub(super) const THERMOCOUPLE_R: Thermocouple = Thermocouple {
+    segments: &[
+        (1064.18, &[0., 5.28961729765e-3, 1.39166589782e-5, -2.38855693017e-8, 3.56916001063e-11,
+                    -4.62347666298e-14, 5.00777441034e-17, -3.73105886191e-20, 1.57716482367e-23,
+                    -2.81038625251e-27]),
+        (1664.5, &[2.95157925316, -2.52061251332e-3, 1.59564501865e-5, -7.64085947576e-9, 2.05305291024e-12,
+                   -2.93359668173e-16]),
+        (1768.1, &[1.52232118209e2, -2.68819888545e-1, 1.71280280471e-4, -3.45895706453e-8, -9.34633971046e-15])
+    ],
+    exponential: None,
+    range: (-50., 1768.)
+};
+
+pub(super) const THERMOCOUPLE_S: Thermocouple = Thermocouple {
+    segments: &[
+        (1064.18, &[0., 5.40313308631e-3, 1.25934289740e-5, -2.32477968689e-8, 3.22028823036e-11,
+                    -3.31465196389e-14, 2.55744251786e-17, -1.25068871393e-20, 2.71443176145e-24]),
+        (1664.5, &[1.32900444085, 3.34509311344e-3, 6.54805192818e-6, -1.64856259209e-9, 1.29989605174e-14]),
+        (1768.1, &[1.46628232636e2, -2.58430516752e-1, 1.63693574641e-4, -3.30439046987e-8, -9.43223690612e-15])
+    ],
+    exponential: None,
+    range: (-50., 1768.)
+};
+
+pub(super) const THERMOCOUPLE_T: Thermocouple = Thermocouple {
+    segments: &[
+        (0., &[0., 3.8748106364e-2, 4.4194434347e-5, 1.1844323105e-7, 2.0032973554e-8, 9.0138019559e-10,
+               2.2651156593e-11, 3.6071154205e-13, 3.8493939883e-15, 2.8213521925e-17, 1.4251594779e-19,
+               4.8768662286e-22, 1.0795539270e-24, 1.3945027062e-27, 7.9795153927e-31]),
+        (400., &[0., 3.8748106364e-2, 3.3292227880e-5, 2.0618243404e-7, -2.1882256846e-9, 1.0996880928e-11,
+                 -3.0815758772e-14, 4.5479135290e-17, -2.7512901673e-20])
+    ],
+    exponential: None,
+    range: (-200., 400.)
+};
+
+// the emf of type B thermocouples is ambiguous below ~40°C, the device only covers higher temperatures
+pub(super) const THERMOCOUPLE_B: Thermocouple = Thermocouple {
+    segments: &[
+        (630.615, &[0., -2.4650818346e-4, 5.9040421171e-6, -1.3257931636e-9, 1.5668291901e-12,
+                    -1.6944529240e-15, 6.2990347094e-19]),
+        (1820., &[-3.8938168621, 2.8571747470e-2, -8.4885104785e-5, 1.5785280164e-7, -1.6835344864e-10,
+                  1.1109794013e-13, -4.4515431033e-17, 9.8975640821e-21, -9.3791330289e-25])
+    ],
+    exponential: None,
+    range: (40., 1820.)
+};
+
+/// RTD resistance in Ω
+///
+/// R = R0 * (1 + A*t + B*t^2 + C*(t - 100)*t^3 + D*t^4 + F*t^6), the C term only applies below 0°C
+pub(super) struct Rtd {
+    r0: f64,
+    coefficients: [f64; 5],
+    pub(super) range: (f64, f64)
+}
+
+impl Rtd {
+    /// platinum RTD following the Callendar-Van Dusen equation with the coefficients of `curve`
+    pub(super) fn platinum(r0: f64, curve: &RTDCurve) -> Self {
+        let [a, b, c] = match curve {
+            RTDCurve::EuropeanStandard => [3.9083e-3, -5.775e-7, -4.183e-12],
+            RTDCurve::American         => [3.9692e-3, -5.8495e-7, -4.233e-12],
+            RTDCurve::Japanese         => [3.9739e-3, -5.870e-7, -4.4e-12],
+            RTDCurve::ITS_90           => [3.9848e-3, -5.870e-7, -4.0e-12]
+        };
+        Rtd { r0, coefficients: [a, b, c, 0., 0.], range: (-200., 850.) }
+    }
+
+    /// 1kΩ platinum RTD with a temperature coefficient of 0.00375
+    pub(super) fn platinum_375() -> Self {
+        Rtd { r0: 1000., coefficients: [3.81e-3, -6.02e-7, -6.0e-12, 0., 0.], range: (-200., 850.) }
+    }
+
+    /// 120Ω nickel RTD (DIN 43760)
+    pub(super) fn nickel_120() -> Self {
+        Rtd { r0: 120., coefficients: [5.485e-3, 6.65e-6, 0., 2.805e-11, -2.0e-17], range: (-80., 260.) }
+    }
+
+    pub(super) fn resistance(&self, celsius: f64) -> f64 {
+        let [a, b, c, d, f] = self.coefficients;
+        let c = if celsius < 0. { c * (celsius - 100.) * celsius.powi(3) } else { 0. };
+        self.r0 * (1. + a * celsius + b * celsius.powi(2) + c + d * celsius.powi(4) + f * celsius.powi(6))
+    }
+}
+
+/// thermistor described by the coefficients of the Steinhart-Hart equation
+///
+/// 1/T = A + B*ln(R) + C*ln(R)^2 + D*ln(R)^3 + E*ln(R)^4 + F*ln(R)^5, with T in K
+pub(super) struct Thermistor {
+    pub(super) coefficients: [f64; 6]
+}
+
+impl Thermistor {
+    pub(super) const RANGE: (f64, f64) = (-40., 150.);
+
+    // 2.252kΩ at 25°C
+    pub(super) const YSI_44004: Self = Self::classic(1.4682e-3, 2.3837e-4, 1.0070e-7);
+    // 3kΩ at 25°C
+    pub(super) const YSI_44005: Self = Self::classic(1.4051e-3, 2.3690e-4, 1.0190e-7);
+    // 5kΩ at 25°C
+    pub(super) const YSI_44007: Self = Self::classic(1.2854e-3, 2.3621e-4, 9.2850e-8);
+    // 10kΩ at 25°C
+    pub(super) const YSI_44006: Self = Self::classic(1.0320e-3, 2.3870e-4, 1.5800e-7);
+    // 30kΩ at 25°C
+    pub(super) const YSI_44008: Self = Self::classic(9.3760e-4, 2.2080e-4, 1.2760e-7);
+    // 2.252kΩ at 25°C
+    pub(super) const YSI_400: Self = Self::classic(1.4705e-3, 2.3780e-4, 1.0389e-7);
+    // 1kΩ at 25°C
+    pub(super) const SPECTRUM_1003K: Self = Self::classic(1.6630e-3, 2.4000e-4, 1.0000e-7);
+
+    // the common three term form 1/T = A + B*ln(R) + C*ln(R)^3
+    const fn classic(a: f64, b: f64, c: f64) -> Self {
+        Thermistor { coefficients: [a, b, 0., c, 0., 0.] }
+    }
+
+    pub(super) fn temperature(&self, ohm: f64) -> f64 {
+        1. / polynomial(&self.coefficients, ohm.ln()) - ZERO_CELSIUS
+    }
+
+    pub(super) fn resistance(&self, celsius: f64) -> f64 {
+        // the temperature falls with increasing resistance, search in ln(R) between 1Ω and 100MΩ
+        let (ln_ohm, _) = invert(|ln_ohm| -self.temperature(ln_ohm.exp()), -celsius, (0., 18.4));
+        ln_ohm.exp()
+    }
+}
+
+/// forward voltage in V of a diode with `ideality` factor at `current` in A
+pub(super) fn diode_voltage(ideality: f64, current: f64, celsius: f64) -> f64 {
+    ideality * thermal_voltage(celsius) * (current / DIODE_SATURATION_CURRENT).ln()
+}
+
+/// kT/q in V
+pub(super) fn thermal_voltage(celsius: f64) -> f64 {
+    BOLTZMANN * (celsius + ZERO_CELSIUS) / ELEMENTARY_CHARGE
+}
+
+/// linear interpolation between the points of a custom table, extrapolates beyond its ends
+pub(super) fn interpolate(points: &[(f64, f64)], x: f64) -> f64 {
+    let increasing = points[0].0 < points[points.len() - 1].0;
+    let segment = points.windows(2)
+        .position(|pair| if increasing { x <= pair[1].0 } else { x >= pair[1].0 })
+        .unwrap_or(points.len() - 2);
+    let ((x0, y0), (x1, y1)) = (points[segment], points[segment + 1]);
+    y0 + (x - x0) * (y1 - y0) / (x1 - x0)
+}
+
+/// find the temperature in `range` at which the monotonic `model` produces `target`
+///
+/// targets the model does not reach within the range are reported at the nearest end of the range
+/// together with the over or under range fault the device signals
+pub(super) fn invert(model: impl Fn(f64) -> f64, target: f64, range: (f64, f64)) -> (f64, FaultFlags) {
+    let (mut low, mut high) = range;
+    let increasing = model(high) > model(low);
+    let above = |celsius: f64| (model(celsius) > target) == increasing;
+    if !above(high) {
+        return (high, FaultFlags::SENSOR_OVER_RANGE);
+    }
+    if above(low) {
+        return (low, FaultFlags::SENSOR_UNDER_RANGE);
+    }
+    for _ in 0..64 {
+        let middle = (low + high) / 2.;
+        if above(middle) {
+            high = middle;
+        } else {
+            low = middle;
+        }
+    }
+    ((low + high) / 2., FaultFlags::empty())
+}
+
+fn polynomial(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().rev().fold(0., |sum, coefficient| sum * x + coefficient)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(value: f64, expected: f64, tolerance: f64) {
+        assert!((value - expected).abs() < tolerance, "{value} differs from {expected}");
+    }
+
+    #[test]
+    fn test_thermocouple_reference_values() {
+        // values of the NIST ITS-90 thermocouple tables in mV
+        assert_close(THERMOCOUPLE_J.emf(100.), 5.269, 1e-3);
+        assert_close(THERMOCOUPLE_J.emf(1000.), 57.953, 1e-3);
+        assert_close(THERMOCOUPLE_K.emf(-100.), -3.554, 1e-3);
+        assert_close(THERMOCOUPLE_K.emf(100.), 4.096, 1e-3);
+        assert_close(THERMOCOUPLE_K.emf(1000.), 41.276, 1e-3);
+        assert_close(THERMOCOUPLE_E.emf(100.), 6.319, 1e-3);
+        assert_close(THERMOCOUPLE_N.emf(100.), 2.774, 1e-3);
+        assert_close(THERMOCOUPLE_R.emf(1000.), 10.506, 1e-3);
+        assert_close(THERMOCOUPLE_S.emf(1000.), 9.587, 1e-3);
+        assert_close(THERMOCOUPLE_T.emf(-100.), -3.379, 1e-3);
+        assert_close(THERMOCOUPLE_T.emf(100.), 4.279, 1e-3);
+        assert_close(THERMOCOUPLE_B.emf(1000.), 4.834, 1e-3);
+
+        let (celsius, faults) = invert(|t| THERMOCOUPLE_K.emf(t), 41.276, THERMOCOUPLE_K.range);
+        assert_close(celsius, 1000., 0.05);
+        assert!(faults.is_empty());
+        assert_eq!(invert(|t| THERMOCOUPLE_T.emf(t), 30., THERMOCOUPLE_T.range), (400., FaultFlags::SENSOR_OVER_RANGE));
+    }
+
+    #[test]
+    fn test_resistive_sensors() {
+        assert_close(Rtd::platinum(100., &RTDCurve::EuropeanStandard).resistance(0.), 100., 1e-9);
+        assert_close(Rtd::platinum(100., &RTDCurve::EuropeanStandard).resistance(100.), 138.5055, 1e-3);
+        assert_close(Rtd::platinum(100., &RTDCurve::EuropeanStandard).resistance(-100.), 60.2558, 1e-3);
+        assert_close(Rtd::nickel_120().resistance(0.), 120., 1e-9);
+
+        assert_close(Thermistor::YSI_44006.resistance(25.), 10000., 50.);
+        assert_close(Thermistor::YSI_44006.temperature(Thermistor::YSI_44006.resistance(-12.5)), -12.5, 1e-6);
+        assert_close(interpolate(&[(3., 10.), (2., 20.), (1., 30.)], 2.5), 15., 1e-9);
+        assert_close(interpolate(&[(1., 10.), (2., 20.)], 3.), 30., 1e-9);
+    }
+}